@@ -92,6 +92,7 @@ pub fn setup_system(
         Session::SyncTest(s) => s.num_players(),
         Session::P2P(s) => s.num_players(),
         Session::Spectator(s) => s.num_players(),
+        Session::Replay(s) => s.num_players(),
     };
 
     // plane
@@ -1,5 +1,5 @@
 use bevy::{prelude::*, window::WindowResolution};
-use bevy_ggrs::prelude::*;
+use bevy_ggrs::{prelude::*, GgrsNetworkStats};
 use clap::Parser;
 use ggrs::UdpNonBlockingSocket;
 use std::net::SocketAddr;
@@ -98,42 +98,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn print_events_system(mut session: ResMut<Session<BoxConfig>>) {
-    match session.as_mut() {
-        Session::P2P(s) => {
-            for event in s.events() {
-                match event {
-                    GgrsEvent::Disconnected { .. } | GgrsEvent::NetworkInterrupted { .. } => {
-                        warn!("GGRS event: {event:?}")
-                    }
-                    GgrsEvent::DesyncDetected { .. } => error!("GGRS event: {event:?}"),
-                    _ => info!("GGRS event: {event:?}"),
-                }
+fn print_events_system(mut events: EventReader<GgrsSessionEvent<BoxConfig>>) {
+    for event in events.iter() {
+        match **event {
+            GgrsEvent::Disconnected { .. } | GgrsEvent::NetworkInterrupted { .. } => {
+                warn!("GGRS event: {:?}", **event)
             }
+            GgrsEvent::DesyncDetected { .. } => error!("GGRS event: {:?}", **event),
+            _ => info!("GGRS event: {:?}", **event),
         }
-        _ => panic!("This example focuses on p2p."),
     }
 }
 
 fn print_network_stats_system(
     time: Res<Time>,
     mut timer: ResMut<NetworkStatsTimer>,
-    p2p_session: Option<Res<Session<BoxConfig>>>,
+    network_stats: Res<GgrsNetworkStats>,
 ) {
     // print only when timer runs out
     if timer.0.tick(time.delta()).just_finished() {
-        if let Some(sess) = p2p_session {
-            match sess.as_ref() {
-                Session::P2P(s) => {
-                    let num_players = s.num_players();
-                    for i in 0..num_players {
-                        if let Ok(stats) = s.network_stats(i) {
-                            println!("NetworkStats for player {}: {:?}", i, stats);
-                        }
-                    }
-                }
-                _ => panic!("This examples focuses on p2p."),
-            }
+        for (handle, stats) in network_stats.iter() {
+            println!("NetworkStats for player {}: {:?}", handle, stats);
         }
     }
 }
@@ -0,0 +1,65 @@
+use crate::{GgrsSessionState, HighestSimulatedFrame, RollbackFrameCount};
+use bevy::prelude::*;
+
+/// A run condition which returns `true` while the [`GgrsSchedule`](`crate::GgrsSchedule`) is
+/// simulating a frame again after a rollback.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::prelude::*;
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// fn count_resimulated_frames(mut count: Local<u32>) {
+///     *count += 1;
+/// }
+///
+/// app.add_systems(
+///     GgrsSchedule,
+///     count_resimulated_frames.run_if(is_resimulating()),
+/// );
+/// # }
+/// ```
+pub fn is_resimulating(
+) -> impl FnMut(Res<RollbackFrameCount>, Res<HighestSimulatedFrame>) -> bool + Clone {
+    |frame: Res<RollbackFrameCount>, highest: Res<HighestSimulatedFrame>| frame.0 <= highest.0
+}
+
+/// A run condition which returns `true` while the [`GgrsSchedule`](`crate::GgrsSchedule`) is
+/// simulating a frame for the first time. This is useful for cosmetic systems, such as those
+/// spawning particles or playing sounds, which should not repeat their work during a rollback.
+/// Systems skipped this way must not modify any rolled back state, or the simulation will no
+/// longer be deterministic.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::prelude::*;
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// fn spawn_footstep_particles() {}
+///
+/// app.add_systems(
+///     GgrsSchedule,
+///     spawn_footstep_particles.run_if(is_first_simulation()),
+/// );
+/// # }
+/// ```
+pub fn is_first_simulation(
+) -> impl FnMut(Res<RollbackFrameCount>, Res<HighestSimulatedFrame>) -> bool + Clone {
+    |frame: Res<RollbackFrameCount>, highest: Res<HighestSimulatedFrame>| frame.0 > highest.0
+}
+
+/// A run condition which returns `true` while the [`Session`](`crate::Session`) is advancing the
+/// rollback simulation, see [`GgrsSessionState::Running`].
+pub fn is_session_running() -> impl FnMut(Res<State<GgrsSessionState>>) -> bool + Clone {
+    |state: Res<State<GgrsSessionState>>| *state.get() == GgrsSessionState::Running
+}
+
+/// A run condition which returns `true` while the [`Session`](`crate::Session`) is waiting for its
+/// remote peers, see [`GgrsSessionState::Synchronizing`].
+pub fn is_session_synchronizing() -> impl FnMut(Res<State<GgrsSessionState>>) -> bool + Clone {
+    |state: Res<State<GgrsSessionState>>| *state.get() == GgrsSessionState::Synchronizing
+}
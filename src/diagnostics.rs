@@ -0,0 +1,211 @@
+use crate::{is_session_running, GgrsUpdateSet, SnapshotMemoryRegistry};
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+    utils::{Duration, FixedState},
+};
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// Measurements of the rollback simulation accumulated since the last Bevy update, maintained
+/// while the [`GgrsDiagnosticsPlugin`] is added.
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct RollbackMetrics {
+    /// The number of frames simulated, including resimulated frames.
+    pub frames: u32,
+    /// The number of snapshots loaded.
+    pub rollbacks: u32,
+    /// The total number of frames resimulated after loading a snapshot.
+    pub resimulated_frames: u32,
+    /// The most frames resimulated after loading a single snapshot.
+    pub max_rollback_depth: u32,
+    /// The total time spent in the [`SaveWorld`](`crate::SaveWorld`) schedule.
+    pub save_time: Duration,
+    /// The total time spent in the [`LoadWorld`](`crate::LoadWorld`) schedule.
+    pub load_time: Duration,
+    /// The total time spent in the [`GgrsSchedule`](`crate::GgrsSchedule`).
+    pub advance_time: Duration,
+    /// How many frames this peer is ahead of its remote peers, if running a
+    /// [`P2PSession`](`ggrs::P2PSession`).
+    pub frames_ahead: Option<i32>,
+}
+
+impl RollbackMetrics {
+    /// Record a rollback which resimulated the provided number of frames, if any.
+    pub(crate) fn record_rollback(&mut self, depth: Option<u32>) {
+        let Some(depth) = depth else {
+            return;
+        };
+
+        self.rollbacks += 1;
+        self.resimulated_frames += depth;
+        self.max_rollback_depth = self.max_rollback_depth.max(depth);
+    }
+
+    /// The average number of frames resimulated per rollback.
+    pub fn average_rollback_depth(&self) -> Option<f64> {
+        (self.rollbacks > 0).then(|| self.resimulated_frames as f64 / self.rollbacks as f64)
+    }
+}
+
+/// A [`Plugin`] which registers [`Diagnostics`] for the rollback simulation, such as the number
+/// of rollbacks per second and the time spent in the [`SaveWorld`](`crate::SaveWorld`),
+/// [`LoadWorld`](`crate::LoadWorld`) and [`GgrsSchedule`](`crate::GgrsSchedule`) schedules.
+///
+/// Measurements are taken once per Bevy update from the [`RollbackMetrics`], so rollback depths
+/// and times describe all frames simulated during that update, rather than a single frame.
+///
+/// While a [`Session`](`crate::Session`) is running, the estimated memory used by the snapshots
+/// of each type registered in the [`SnapshotMemoryRegistry`] is also reported, see
+/// [`snapshot_memory`](`GgrsDiagnosticsPlugin::snapshot_memory`).
+///
+/// # Examples
+/// ```rust
+/// # use bevy::{prelude::*, diagnostic::LogDiagnosticsPlugin};
+/// # use bevy_ggrs::{prelude::*, GgrsDiagnosticsPlugin};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// app.add_plugins((GgrsDiagnosticsPlugin, LogDiagnosticsPlugin::default()));
+/// # }
+/// ```
+pub struct GgrsDiagnosticsPlugin;
+
+impl GgrsDiagnosticsPlugin {
+    pub const ROLLBACKS_PER_SECOND: DiagnosticId =
+        DiagnosticId::from_u128(100_472_637_195_203_398_826_914_736_310_154_092_781);
+    /// The average number of frames resimulated per rollback during an update.
+    pub const ROLLBACK_DEPTH: DiagnosticId =
+        DiagnosticId::from_u128(58_136_120_407_559_934_208_147_826_583_919_506_213);
+    /// The most frames resimulated by a single rollback during an update.
+    pub const MAX_ROLLBACK_DEPTH: DiagnosticId =
+        DiagnosticId::from_u128(254_613_052_813_408_722_149_031_683_627_560_093_154);
+    /// The total time spent saving snapshots during an update, in milliseconds.
+    pub const SAVE_WORLD_TIME: DiagnosticId =
+        DiagnosticId::from_u128(176_226_489_053_790_174_326_157_462_108_311_924_870);
+    /// The total time spent loading snapshots during an update, in milliseconds.
+    pub const LOAD_WORLD_TIME: DiagnosticId =
+        DiagnosticId::from_u128(23_845_902_738_170_652_691_376_027_140_895_566_237);
+    /// The total time spent simulating frames during an update, in milliseconds.
+    pub const ADVANCE_WORLD_TIME: DiagnosticId =
+        DiagnosticId::from_u128(301_945_126_084_367_201_553_980_432_846_017_236_449);
+    pub const FRAMES_AHEAD: DiagnosticId =
+        DiagnosticId::from_u128(139_507_836_214_902_174_886_273_570_418_392_501_606);
+
+    const MAX_HISTORY_LENGTH: usize = 120;
+
+    /// The [`DiagnosticId`] for the snapshot memory of the provided type, as named in the
+    /// [`SnapshotMemoryRegistry`].
+    pub fn snapshot_memory(name: &str) -> DiagnosticId {
+        let mut hasher = FixedState.build_hasher();
+        "bevy_ggrs::snapshot_memory".hash(&mut hasher);
+        name.hash(&mut hasher);
+
+        DiagnosticId::from_u128(hasher.finish() as u128)
+    }
+
+    /// A [`System`] which records the [`RollbackMetrics`] of the last update.
+    pub fn update(
+        mut diagnostics: Diagnostics,
+        mut metrics: ResMut<RollbackMetrics>,
+        time: Res<Time>,
+    ) {
+        let metrics = std::mem::take(metrics.as_mut());
+        let delta = time.delta_seconds_f64();
+
+        if delta > 0. {
+            diagnostics.add_measurement(Self::ROLLBACKS_PER_SECOND, || {
+                metrics.rollbacks as f64 / delta
+            });
+        }
+
+        if let Some(depth) = metrics.average_rollback_depth() {
+            diagnostics.add_measurement(Self::ROLLBACK_DEPTH, || depth);
+            diagnostics.add_measurement(Self::MAX_ROLLBACK_DEPTH, || {
+                metrics.max_rollback_depth as f64
+            });
+        }
+
+        if metrics.frames > 0 {
+            diagnostics.add_measurement(Self::SAVE_WORLD_TIME, || {
+                metrics.save_time.as_secs_f64() * 1000.
+            });
+            diagnostics.add_measurement(Self::LOAD_WORLD_TIME, || {
+                metrics.load_time.as_secs_f64() * 1000.
+            });
+            diagnostics.add_measurement(Self::ADVANCE_WORLD_TIME, || {
+                metrics.advance_time.as_secs_f64() * 1000.
+            });
+        }
+
+        if let Some(frames_ahead) = metrics.frames_ahead {
+            diagnostics.add_measurement(Self::FRAMES_AHEAD, || frames_ahead as f64);
+        }
+    }
+
+    /// A [`System`] which records the memory used by every registered snapshot. This walks every
+    /// stored snapshot, so only runs while a [`Session`](`crate::Session`) is running.
+    pub fn update_snapshot_memory(world: &World, mut diagnostics: Diagnostics) {
+        let Some(registry) = world.get_resource::<SnapshotMemoryRegistry>() else {
+            return;
+        };
+
+        for (name, bytes) in registry.measure(world) {
+            diagnostics.add_measurement(Self::snapshot_memory(name), || bytes as f64);
+        }
+    }
+}
+
+impl Plugin for GgrsDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let diagnostic = |id, name| Diagnostic::new(id, name, Self::MAX_HISTORY_LENGTH);
+
+        app.init_resource::<RollbackMetrics>()
+            .register_diagnostic(diagnostic(
+                Self::ROLLBACKS_PER_SECOND,
+                "ggrs_rollbacks_per_second",
+            ))
+            .register_diagnostic(diagnostic(Self::ROLLBACK_DEPTH, "ggrs_rollback_depth"))
+            .register_diagnostic(diagnostic(
+                Self::MAX_ROLLBACK_DEPTH,
+                "ggrs_max_rollback_depth",
+            ))
+            .register_diagnostic(
+                diagnostic(Self::SAVE_WORLD_TIME, "ggrs_save_world_time").with_suffix("ms"),
+            )
+            .register_diagnostic(
+                diagnostic(Self::LOAD_WORLD_TIME, "ggrs_load_world_time").with_suffix("ms"),
+            )
+            .register_diagnostic(
+                diagnostic(Self::ADVANCE_WORLD_TIME, "ggrs_advance_world_time").with_suffix("ms"),
+            )
+            .register_diagnostic(diagnostic(Self::FRAMES_AHEAD, "ggrs_frames_ahead"))
+            .add_systems(
+                PreUpdate,
+                (
+                    Self::update,
+                    Self::update_snapshot_memory.run_if(is_session_running()),
+                )
+                    .after(GgrsUpdateSet),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        // Snapshot plugins may be added after this plugin, so they are registered once all are built
+        let names = app
+            .world
+            .get_resource::<SnapshotMemoryRegistry>()
+            .map(|registry| registry.names().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        for name in names {
+            app.register_diagnostic(
+                Diagnostic::new(
+                    Self::snapshot_memory(name),
+                    format!("ggrs_snapshot_memory/{}", bevy::utils::get_short_name(name)),
+                    Self::MAX_HISTORY_LENGTH,
+                )
+                .with_suffix("B"),
+            );
+        }
+    }
+}
@@ -0,0 +1,451 @@
+use crate::{
+    CloneStrategy, ConfirmedFrameCount, GgrsSessionState, GgrsUpdateSet, RequestsHandled,
+    ResourceSnapshotPlugin, RollbackFrameCount,
+};
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::{hash::Hash, marker::PhantomData};
+
+/// A rolled back queue of side effects (sounds, particles, etc.) of type `E`, tagged with the
+/// [`RollbackFrameCount`] they were queued in.
+///
+/// Effects are only dispatched as [`ConfirmedEffect`] events once their frame has been confirmed.
+/// If a rollback resimulates a frame without queueing an effect again, the effect is discarded
+/// along with the rest of the mispredicted state.
+#[derive(Resource, Clone)]
+pub struct ConfirmedEffects<E> {
+    effects: Vec<(i32, E)>,
+}
+
+impl<E> Default for ConfirmedEffects<E> {
+    fn default() -> Self {
+        Self {
+            effects: Vec::new(),
+        }
+    }
+}
+
+impl<E> ConfirmedEffects<E> {
+    /// Queue an effect for the provided frame.
+    pub fn queue(&mut self, frame: i32, effect: E) -> &mut Self {
+        self.effects.push((frame, effect));
+        self
+    }
+
+    /// Iterate over all queued effects as `(frame, effect)`.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &E)> + '_ {
+        self.effects.iter().map(|(frame, effect)| (*frame, effect))
+    }
+
+    /// The number of queued effects.
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// Returns `true` if there are no queued effects, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+}
+
+/// Queues [`ConfirmedEffects`] of type `E` for the current frame.
+#[derive(SystemParam)]
+pub struct ConfirmedEffectWriter<'w, E: Send + Sync + 'static> {
+    effects: ResMut<'w, ConfirmedEffects<E>>,
+    frame: Res<'w, RollbackFrameCount>,
+}
+
+impl<'w, E: Send + Sync + 'static> ConfirmedEffectWriter<'w, E> {
+    /// Queue an effect, which will be dispatched once the current frame is confirmed.
+    pub fn queue(&mut self, effect: E) {
+        let frame = self.frame.0;
+        self.effects.queue(frame, effect);
+    }
+}
+
+/// Event sent once a queued effect has been confirmed.
+#[derive(Event, Clone, Debug)]
+pub struct ConfirmedEffect<E> {
+    /// The frame the effect was queued in.
+    pub frame: i32,
+    pub effect: E,
+}
+
+/// A [`Plugin`] which registers [`ConfirmedEffects`] of type `E`, and dispatches them as
+/// [`ConfirmedEffect`] events once their frame is no later than the [`ConfirmedFrameCount`].
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, ConfirmedEffect, ConfirmedEffectPlugin, ConfirmedEffectWriter};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// #[derive(Clone)]
+/// struct PlaySound(&'static str);
+///
+/// app.add_plugins(ConfirmedEffectPlugin::<PlaySound>::default());
+///
+/// // Inside the rollback schedule, queue the effect
+/// fn hit(mut effects: ConfirmedEffectWriter<PlaySound>) {
+///     effects.queue(PlaySound("hit.ogg"));
+/// }
+///
+/// // Outside the rollback schedule, play it once it can no longer be rolled back
+/// fn play(mut sounds: EventReader<ConfirmedEffect<PlaySound>>) {
+///     for sound in sounds.iter() {
+///         // ...
+///     }
+/// }
+///
+/// app.add_systems(GgrsSchedule, hit)
+///     .add_systems(Update, play);
+/// # }
+/// ```
+pub struct ConfirmedEffectPlugin<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    _phantom: PhantomData<E>,
+}
+
+impl<E> Default for ConfirmedEffectPlugin<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: default(),
+        }
+    }
+}
+
+impl<E> ConfirmedEffectPlugin<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    /// A [`System`] which sends a [`ConfirmedEffect`] for every effect which has been confirmed
+    /// since the last time it ran, and removes confirmed effects from the queue.
+    pub fn dispatch(
+        mut effects: ResMut<ConfirmedEffects<E>>,
+        confirmed_frame: Res<ConfirmedFrameCount>,
+        mut dispatched_frame: Local<Option<i32>>,
+        mut events: EventWriter<ConfirmedEffect<E>>,
+    ) {
+        let confirmed_frame = confirmed_frame.0;
+
+        // Snapshots taken before the last dispatch may still contain already dispatched effects.
+        // A confirmed frame moving backwards indicates a new session.
+        let dispatched = dispatched_frame
+            .filter(|&frame| frame <= confirmed_frame)
+            .unwrap_or(i32::MIN);
+
+        for (frame, effect) in effects.iter() {
+            if frame > dispatched && frame <= confirmed_frame {
+                events.send(ConfirmedEffect {
+                    frame,
+                    effect: effect.clone(),
+                });
+            }
+        }
+
+        effects
+            .effects
+            .retain(|&(frame, _)| frame > confirmed_frame);
+
+        *dispatched_frame = Some(confirmed_frame);
+    }
+}
+
+impl<E> Plugin for ConfirmedEffectPlugin<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConfirmedEffects<E>>()
+            .add_event::<ConfirmedEffect<E>>()
+            .add_plugins(ResourceSnapshotPlugin::<CloneStrategy<ConfirmedEffects<E>>>::default())
+            .add_systems(PreUpdate, Self::dispatch.after(GgrsUpdateSet));
+    }
+}
+
+/// A rolled back registry of predicted effects, keyed by a stable identifier `K`
+/// (for example, a [`Rollback`](`crate::Rollback`) paired with an effect id).
+///
+/// Systems in the [`GgrsSchedule`](`crate::GgrsSchedule`) declare effects with a
+/// [`PredictedEffectWriter`]. After every batch of requests, the set of declared effects is compared
+/// against the effects which were previously started, emitting [`PredictedEffectEvent`]s for any
+/// which were started or cancelled by a rollback. An effect lasts for as long as its key is
+/// declared on every consecutive frame, so effects may be declared on every frame they apply to.
+/// Declaring a key again after a frame without it starts a new effect.
+#[derive(Resource, Clone)]
+pub struct PredictedEffects<K> {
+    /// Declared keys, and the first and last frame of every effect declared with them which may
+    /// still be cancelled or continued, oldest first.
+    effects: HashMap<K, Vec<(i32, i32)>>,
+}
+
+impl<K> Default for PredictedEffects<K> {
+    fn default() -> Self {
+        Self {
+            effects: HashMap::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash> PredictedEffects<K> {
+    /// Declare an effect during the provided frame, continuing it if it was also declared during
+    /// the previous frame.
+    pub fn declare(&mut self, frame: i32, key: K) -> &mut Self {
+        let effects = self.effects.entry(key).or_default();
+
+        match effects.last_mut() {
+            Some((_, last)) if *last >= frame - 1 => *last = (*last).max(frame),
+            _ => effects.push((frame, frame)),
+        }
+
+        self
+    }
+
+    /// Get the frame the latest effect for a key was first declared in, if it is still tracked.
+    pub fn get(&self, key: &K) -> Option<i32> {
+        self.effects
+            .get(key)
+            .and_then(|effects| effects.last())
+            .map(|&(first, _)| first)
+    }
+
+    /// Iterate over the latest effect for every tracked key as `(key, frame)`, where `frame` is
+    /// the frame it was first declared in.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, i32)> + '_ {
+        self.effects
+            .iter()
+            .filter_map(|(key, effects)| effects.last().map(|&(first, _)| (key, first)))
+    }
+
+    /// The number of tracked keys.
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// Returns `true` if there are no tracked keys, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// The last frame the effect for a key first declared in `first` was declared in, if it is
+    /// still tracked.
+    fn last_frame(&self, key: &K, first: i32) -> Option<i32> {
+        self.effects
+            .get(key)?
+            .iter()
+            .find(|&&(frame, _)| frame == first)
+            .map(|&(_, last)| last)
+    }
+
+    /// Forget every effect which was last declared before the provided frame.
+    fn forget_before(&mut self, frame: i32) {
+        self.effects.retain(|_, effects| {
+            effects.retain(|&(_, last)| last >= frame);
+            !effects.is_empty()
+        });
+    }
+}
+
+/// Declares [`PredictedEffects`] of type `K` during the current frame.
+#[derive(SystemParam)]
+pub struct PredictedEffectWriter<'w, K: Eq + Hash + Send + Sync + 'static> {
+    effects: ResMut<'w, PredictedEffects<K>>,
+    frame: Res<'w, RollbackFrameCount>,
+}
+
+impl<'w, K: Eq + Hash + Send + Sync + 'static> PredictedEffectWriter<'w, K> {
+    /// Declare an effect. If an effect with the same key was declared during the previous frame,
+    /// it continues instead of starting again.
+    pub fn declare(&mut self, key: K) {
+        let frame = self.frame.0;
+        self.effects.declare(frame, key);
+    }
+}
+
+/// The effects of type `K` which [`PredictedEffectPlugin`] has sent a
+/// [`PredictedEffectEvent::Started`] for, and which may still be cancelled.
+///
+/// This is not rolled back, and is cleared once the session stops running.
+#[derive(Resource)]
+pub struct ActivePredictedEffects<K> {
+    /// Started effects as `(key, first frame)`.
+    active: HashSet<(K, i32)>,
+    /// The confirmed frame during the last dispatch.
+    handled_frame: Option<i32>,
+}
+
+impl<K> Default for ActivePredictedEffects<K> {
+    fn default() -> Self {
+        Self {
+            active: HashSet::default(),
+            handled_frame: None,
+        }
+    }
+}
+
+impl<K> ActivePredictedEffects<K> {
+    /// The number of started effects.
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Returns `true` if no effects are started, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.active.clear();
+        self.handled_frame = None;
+    }
+}
+
+/// Event sent when the set of [`PredictedEffects`] changes after a batch of requests.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub enum PredictedEffectEvent<K> {
+    /// The effect was declared, and should start immediately.
+    Started { key: K, frame: i32 },
+    /// A rollback removed the effect, and it should be stopped.
+    Cancelled { key: K, frame: i32 },
+}
+
+/// A [`Plugin`] which registers [`PredictedEffects`] of type `K`, and sends
+/// [`PredictedEffectEvent`]s as effects are started and cancelled.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, PredictedEffectEvent, PredictedEffectPlugin, PredictedEffectWriter};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// #[derive(Clone, PartialEq, Eq, Hash)]
+/// struct HitSound(Rollback);
+///
+/// app.add_plugins(PredictedEffectPlugin::<HitSound>::default());
+///
+/// fn hit(mut effects: PredictedEffectWriter<HitSound>, players: Query<&Rollback>) {
+///     for &player in players.iter() {
+///         effects.declare(HitSound(player));
+///     }
+/// }
+///
+/// fn play(mut events: EventReader<PredictedEffectEvent<HitSound>>) {
+///     for event in events.iter() {
+///         match event {
+///             PredictedEffectEvent::Started { .. } => { /* play */ }
+///             PredictedEffectEvent::Cancelled { .. } => { /* stop */ }
+///         }
+///     }
+/// }
+///
+/// app.add_systems(GgrsSchedule, hit)
+///     .add_systems(Update, play);
+/// # }
+/// ```
+pub struct PredictedEffectPlugin<K>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+{
+    _phantom: PhantomData<K>,
+}
+
+impl<K> Default for PredictedEffectPlugin<K>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: default(),
+        }
+    }
+}
+
+impl<K> PredictedEffectPlugin<K>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+{
+    /// A [`System`] which compares the declared [`PredictedEffects`] against the previously
+    /// started effects, sending [`PredictedEffectEvent`]s for the difference. Effects which ended
+    /// before the [`ConfirmedFrameCount`] are forgotten, as they can no longer be cancelled or
+    /// continued.
+    ///
+    /// Runs in the [`RequestsHandled`] schedule, so an effect which is declared and cancelled
+    /// while several frames are advanced during a single update is still started and cancelled.
+    pub fn dispatch(
+        mut effects: ResMut<PredictedEffects<K>>,
+        confirmed_frame: Res<ConfirmedFrameCount>,
+        mut active: ResMut<ActivePredictedEffects<K>>,
+        mut events: EventWriter<PredictedEffectEvent<K>>,
+    ) {
+        let confirmed_frame = confirmed_frame.0;
+
+        // Snapshots may still contain effects which ended and were forgotten.
+        // A confirmed frame moving backwards indicates a new session.
+        match active.handled_frame {
+            Some(handled) if handled <= confirmed_frame => effects.forget_before(handled),
+            Some(_) => active.clear(),
+            None => {}
+        }
+
+        let ActivePredictedEffects {
+            active,
+            handled_frame,
+        } = &mut *active;
+
+        for (key, declared) in effects.effects.iter() {
+            for &(first, _) in declared {
+                if active.insert((key.clone(), first)) {
+                    events.send(PredictedEffectEvent::Started {
+                        key: key.clone(),
+                        frame: first,
+                    });
+                }
+            }
+        }
+
+        active.retain(|(key, first)| match effects.last_frame(key, *first) {
+            Some(last) => last >= confirmed_frame,
+            None => {
+                events.send(PredictedEffectEvent::Cancelled {
+                    key: key.clone(),
+                    frame: *first,
+                });
+                false
+            }
+        });
+
+        effects.forget_before(confirmed_frame);
+
+        *handled_frame = Some(confirmed_frame);
+    }
+
+    /// A [`System`] which forgets the started effects once the session stops running, so they
+    /// are not carried over into the next session.
+    pub fn reset(mut active: ResMut<ActivePredictedEffects<K>>) {
+        active.clear();
+    }
+}
+
+impl<K> Plugin for PredictedEffectPlugin<K>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PredictedEffects<K>>()
+            .init_resource::<ActivePredictedEffects<K>>()
+            .add_event::<PredictedEffectEvent<K>>()
+            .add_plugins(ResourceSnapshotPlugin::<CloneStrategy<PredictedEffects<K>>>::default())
+            .add_systems(RequestsHandled, Self::dispatch)
+            .add_systems(OnExit(GgrsSessionState::Running), Self::reset);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{GgrsUpdateSet, Rollback, RollbackFrameCount, RollbackTimestepFraction};
+use bevy::prelude::*;
+use std::marker::PhantomData;
+
+/// Describes how to blend between two values of a type.
+pub trait Interpolate {
+    /// Blend between `self` (at `t = 0.0`) and `other` (at `t = 1.0`).
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        *self + (*other - *self) * t
+    }
+}
+
+impl Interpolate for Vec2 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Vec3 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl Interpolate for Quat {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.slerp(*other, t)
+    }
+}
+
+impl Interpolate for Transform {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Transform {
+            translation: self.translation.interpolate(&other.translation, t),
+            rotation: self.rotation.interpolate(&other.rotation, t),
+            scale: self.scale.interpolate(&other.scale, t),
+        }
+    }
+}
+
+/// The values of a [`Component`] `C` at the previous and current simulated frame,
+/// maintained by [`InterpolationPlugin`].
+#[derive(Component, Clone, Debug)]
+pub struct InterpolationState<C> {
+    pub previous: C,
+    pub current: C,
+}
+
+/// The interpolated value of a [`Component`] `C`, suitable for rendering.
+/// This is maintained by [`InterpolationPlugin`] and is never rolled back.
+#[derive(Component, Clone, Debug, Deref, DerefMut)]
+pub struct Interpolated<C>(pub C);
+
+/// A [`Plugin`] which blends the [`Component`] `C` on [`Rollback`] entities between the previous
+/// and current simulated frame, using the [`RollbackTimestepFraction`]. The result is written to
+/// a separate [`Interpolated`] component, so the rolled back state is never touched.
+///
+/// Interpolated values trail the simulation by up to one frame. If more than one frame is
+/// simulated in a single update, interpolation starts from the last value that was observed.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, InterpolationPlugin, Interpolated};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// app.rollback_component_with_clone::<Transform>();
+///
+/// // Every rollback entity with a Transform will also receive an Interpolated<Transform>
+/// app.add_plugins(InterpolationPlugin::<Transform>::default());
+/// # }
+/// ```
+pub struct InterpolationPlugin<C>
+where
+    C: Component + Interpolate + Clone,
+{
+    _phantom: PhantomData<C>,
+}
+
+impl<C> Default for InterpolationPlugin<C>
+where
+    C: Component + Interpolate + Clone,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: default(),
+        }
+    }
+}
+
+impl<C> InterpolationPlugin<C>
+where
+    C: Component + Interpolate + Clone,
+{
+    /// A [`System`] which records the latest simulated value of `C` whenever it changes. If the
+    /// simulation advanced without changing `C`, the entity is considered at rest.
+    #[allow(clippy::type_complexity)]
+    pub fn track(
+        mut commands: Commands,
+        frame: Res<RollbackFrameCount>,
+        mut query: Query<(Entity, Ref<C>, Option<&mut InterpolationState<C>>), With<Rollback>>,
+    ) {
+        for (entity, component, state) in query.iter_mut() {
+            match state {
+                Some(mut state) if component.is_changed() => {
+                    state.previous = std::mem::replace(&mut state.current, component.clone());
+                }
+                Some(mut state) if frame.is_changed() => {
+                    state.previous = state.current.clone();
+                }
+                Some(_) => {}
+                None => {
+                    commands.entity(entity).insert((
+                        InterpolationState {
+                            previous: component.clone(),
+                            current: component.clone(),
+                        },
+                        Interpolated(component.clone()),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// A [`System`] which updates [`Interpolated`] from the [`InterpolationState`].
+    pub fn interpolate(
+        fraction: Res<RollbackTimestepFraction>,
+        mut query: Query<(&InterpolationState<C>, &mut Interpolated<C>)>,
+    ) {
+        for (state, mut interpolated) in query.iter_mut() {
+            interpolated.0 = state.previous.interpolate(&state.current, fraction.0);
+        }
+    }
+}
+
+impl<C> Plugin for InterpolationPlugin<C>
+where
+    C: Component + Interpolate + Clone,
+{
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (Self::track, Self::interpolate)
+                .chain()
+                .after(GgrsUpdateSet),
+        );
+    }
+}
@@ -9,22 +9,47 @@ use bevy::{
     prelude::*,
     utils::{Duration, HashMap},
 };
-use ggrs::{Config, InputStatus, P2PSession, PlayerHandle, SpectatorSession, SyncTestSession};
-use std::{fmt::Debug, hash::Hash, marker::PhantomData, net::SocketAddr};
+use ggrs::{
+    Config, GGRSEvent, InputStatus, P2PSession, PlayerHandle, SessionState, SpectatorSession,
+    SyncTestSession,
+};
+use std::{fmt::Debug, hash::Hash, marker::PhantomData, net::SocketAddr, num::NonZeroUsize};
 
 pub use ggrs;
 
+pub use conditions::*;
+pub use diagnostics::*;
+pub use effects::*;
+pub use interpolation::*;
+pub use network_stats::*;
+pub use profiler::*;
+pub use replay::*;
 pub use rollback::*;
+pub use rollback_events::*;
 pub use snapshot::*;
-
+pub use state_transfer::*;
+pub use time_sync::*;
+
+pub(crate) mod conditions;
+pub(crate) mod diagnostics;
+pub(crate) mod effects;
+pub(crate) mod interpolation;
+pub(crate) mod network_stats;
+pub(crate) mod profiler;
+pub(crate) mod replay;
 pub(crate) mod rollback;
+pub(crate) mod rollback_events;
 pub(crate) mod schedule_systems;
 pub(crate) mod snapshot;
+pub(crate) mod state_transfer;
+pub(crate) mod time_sync;
 
 pub mod prelude {
     pub use crate::{
-        snapshot::prelude::*, AddRollbackCommandExtension, GgrsApp, GgrsConfig, GgrsPlugin,
-        GgrsSchedule, PlayerInputs, ReadInputs, Rollback, Session,
+        is_first_simulation, is_resimulating, is_session_running, is_session_synchronizing,
+        snapshot::prelude::*, AddRollbackCommandExtension, DespawnRollbackCommandExtension,
+        GgrsApp, GgrsConfig, GgrsPlugin, GgrsSchedule, GgrsSessionEvent, GgrsSessionState,
+        PlayerInputs, ReadInputs, Rollback, RollbackEventReader, RollbackEventWriter, Session,
     };
     pub use ggrs::{GGRSEvent as GgrsEvent, PlayerType, SessionBuilder};
 }
@@ -62,6 +87,49 @@ pub enum Session<T: Config> {
     SyncTest(SyncTestSession<T>),
     P2P(P2PSession<T>),
     Spectator(SpectatorSession<T>),
+    Replay(ReplaySession<T>),
+}
+
+/// The lifecycle of the [`Session`], maintained by the [`GgrsPlugin`] as a Bevy [`State`], so
+/// systems can react to it using [`OnEnter`] and [`OnExit`] schedules, or run conditions such as
+/// [`is_session_running`].
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::prelude::*;
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// fn show_loading_screen() {}
+/// fn hide_loading_screen() {}
+/// fn show_disconnected_menu() {}
+///
+/// app.add_systems(OnEnter(GgrsSessionState::Synchronizing), show_loading_screen)
+///     .add_systems(OnExit(GgrsSessionState::Synchronizing), hide_loading_screen)
+///     .add_systems(OnEnter(GgrsSessionState::Disconnected), show_disconnected_menu);
+/// # }
+/// ```
+#[derive(States, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GgrsSessionState {
+    /// No [`Session`] has been inserted yet.
+    #[default]
+    NoSession,
+    /// A [`Session`] is waiting for its remote peers before it can start.
+    Synchronizing,
+    /// A [`Session`] is advancing the rollback simulation.
+    Running,
+    /// The [`Session`] was removed after it had been inserted.
+    Disconnected,
+}
+
+impl From<SessionState> for GgrsSessionState {
+    fn from(value: SessionState) -> Self {
+        match value {
+            SessionState::Synchronizing => Self::Synchronizing,
+            SessionState::Running => Self::Running,
+        }
+    }
 }
 
 // TODO: more specific name to avoid conflicts?
@@ -70,24 +138,134 @@ pub struct PlayerInputs<T: Config>(Vec<(T::Input, InputStatus)>);
 
 #[derive(Resource, Copy, Clone, Debug)]
 struct FixedTimestepData {
-    /// fixed FPS our logic is running with
-    pub fps: usize,
     /// accumulated time. once enough time has been accumulated, an update is executed
     accumulator: Duration,
-    /// boolean to see if we should run slow to let remote clients catch up
-    run_slow: bool,
+    /// how much longer frames should take to let remote clients catch up, see [`TimeSyncPolicy`]
+    frame_time_factor: f64,
 }
 
 impl Default for FixedTimestepData {
     fn default() -> Self {
         Self {
-            fps: DEFAULT_FPS,
             accumulator: Duration::ZERO,
-            run_slow: false,
+            frame_time_factor: 1.,
         }
     }
 }
 
+/// Timing settings for the rollback simulation. Unlike the settings of a [`Session`], these can be
+/// changed at any time, for example to adapt to the measured latency of remote players.
+#[derive(Resource, Copy, Clone, Debug, PartialEq)]
+pub struct RollbackTimingSettings {
+    /// The frequency the [`GgrsSchedule`] is run at.
+    pub fps: usize,
+    /// How much longer each frame takes while running slow to let remote clients catch up,
+    /// as a multiple of the regular frame time. Used by [`RunSlowTimeSync`].
+    pub run_slow_factor: f64,
+    /// Frames of delay applied to local inputs before they are added to the [`Session`],
+    /// in addition to the input delay the [`Session`] was built with.
+    pub input_delay: usize,
+    /// The maximum number of frames simulated in a single Bevy update, or [`None`] for no limit.
+    /// Limiting this prevents the simulation from spiraling after a long hitch. At least one
+    /// frame is always simulated per update, as the simulation would otherwise never advance.
+    pub max_steps_per_update: Option<NonZeroUsize>,
+    /// What to do with the remaining time once
+    /// [`max_steps_per_update`](`RollbackTimingSettings::max_steps_per_update`) is reached.
+    pub catch_up_policy: CatchUpPolicy,
+}
+
+impl Default for RollbackTimingSettings {
+    fn default() -> Self {
+        Self {
+            fps: DEFAULT_FPS,
+            run_slow_factor: 1.1,
+            input_delay: 0,
+            max_steps_per_update: None,
+            catch_up_policy: CatchUpPolicy::Discard,
+        }
+    }
+}
+
+/// Determines what happens to accumulated time which could not be simulated within a single
+/// update, see [`RollbackTimingSettings::max_steps_per_update`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CatchUpPolicy {
+    /// Drop the remaining whole frames. The simulation falls behind wall-clock time, but
+    /// immediately returns to running at a regular pace.
+    #[default]
+    Discard,
+    /// Keep the remaining time, so the frames are simulated during later updates. At most
+    /// [`max_steps_per_update`](`RollbackTimingSettings::max_steps_per_update`) frames are kept,
+    /// and any beyond that are dropped as with [`Discard`](`CatchUpPolicy::Discard`).
+    CarryOver,
+}
+
+/// Sent when [`RollbackTimingSettings::max_steps_per_update`] caused frames to be dropped during
+/// an update, according to the [`CatchUpPolicy`]. Frames which are carried over to later updates
+/// are not reported.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramesSkipped {
+    /// The number of whole frames which were dropped.
+    pub frames: usize,
+    /// The [`CatchUpPolicy`] applied to these frames.
+    pub policy: CatchUpPolicy,
+}
+
+/// A [`GGRSEvent`] from the [`P2PSession`] or [`SpectatorSession`], forwarded by the
+/// [`GgrsPlugin`] every update, so any number of systems can read it using an [`EventReader`].
+/// Dereferences to the [`GGRSEvent`], which the prelude exports as `GgrsEvent`.
+///
+/// Frames reported by these events are offset by the [`SessionStartFrame`], so they match the
+/// [`RollbackFrameCount`] they refer to.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, GgrsUpdateSet};
+/// #
+/// # type MyConfig = GgrsConfig<u8>;
+/// #
+/// # fn start(session: Session<MyConfig>) {
+/// # let mut app = App::new();
+/// fn log_events(mut events: EventReader<GgrsSessionEvent<MyConfig>>) {
+///     for event in events.iter() {
+///         match **event {
+///             GgrsEvent::Disconnected { .. } => warn!("GGRS event: {:?}", **event),
+///             _ => info!("GGRS event: {:?}", **event),
+///         }
+///     }
+/// }
+///
+/// app.add_systems(Update, log_events.after(GgrsUpdateSet));
+/// # }
+/// ```
+#[derive(Event, Deref, DerefMut)]
+pub struct GgrsSessionEvent<C: Config>(pub GGRSEvent<C>);
+
+impl<C: Config> Debug for GgrsSessionEvent<C>
+where
+    GGRSEvent<C>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("GgrsSessionEvent").field(&self.0).finish()
+    }
+}
+
+impl<C: Config> Clone for GgrsSessionEvent<C>
+where
+    GGRSEvent<C>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<C: Config> From<GGRSEvent<C>> for GgrsSessionEvent<C> {
+    fn from(value: GGRSEvent<C>) -> Self {
+        Self(value)
+    }
+}
+
 /// Keeps track of the current frame the rollback simulation is in
 #[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RollbackFrameCount(i32);
@@ -98,6 +276,24 @@ impl From<RollbackFrameCount> for i32 {
     }
 }
 
+/// The highest frame the [`GgrsSchedule`] has finished simulating. While the [`GgrsSchedule`]
+/// runs, any frame up to and including this one is being resimulated after a rollback, see
+/// [`is_resimulating`] and [`is_first_simulation`].
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HighestSimulatedFrame(i32);
+
+impl From<HighestSimulatedFrame> for i32 {
+    fn from(value: HighestSimulatedFrame) -> i32 {
+        value.0
+    }
+}
+
+/// The frame the current [`Session`] started from. GGRS always counts frames from zero, so this
+/// offset is applied to every frame it reports. This is zero unless the simulation was resumed
+/// from a transferred state, see [`import_state`] and [`rewind_to_frame`].
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SessionStartFrame(pub i32);
+
 /// The most recently confirmed frame. Any information for frames stored before this point can be safely discarded.
 #[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ConfirmedFrameCount(i32);
@@ -108,6 +304,12 @@ impl From<ConfirmedFrameCount> for i32 {
     }
 }
 
+/// How far the rollback simulation has progressed towards its next frame, from `0.0` to `1.0`.
+/// This can be used to interpolate between the previous and current simulated frame when
+/// rendering, see [`InterpolationPlugin`].
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct RollbackTimestepFraction(pub f32);
+
 /// The maximum prediction window for this [`Session`], provided as a concrete [`Resource`].
 #[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MaxPredictionWindow(usize);
@@ -120,6 +322,12 @@ pub struct LocalInputs<C: Config>(pub HashMap<PlayerHandle, C::Input>);
 #[derive(Resource, Default)]
 pub struct LocalPlayers(pub Vec<PlayerHandle>);
 
+/// Set for the system which runs the [`ReadInputs`], [`LoadWorld`], [`GgrsSchedule`],
+/// [`SaveWorld`] and [`RequestsHandled`] schedules during [`PreUpdate`]. Systems which react to the outcome of
+/// a rollback update should be ordered after this set.
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct GgrsUpdateSet;
+
 /// Label for the schedule which reads the inputs for the current frame
 #[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
 pub struct ReadInputs;
@@ -132,6 +340,11 @@ pub struct LoadWorld;
 #[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
 pub struct SaveWorld;
 
+/// Label for the schedule which runs after every batch of requests from the [`Session`] has been
+/// handled. When several frames are advanced during a single update, it runs once per frame.
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct RequestsHandled;
+
 /// GGRS plugin for bevy.
 ///
 /// # Examples
@@ -164,42 +377,80 @@ pub struct SaveWorld;
 /// # }
 /// ```
 pub struct GgrsPlugin<C: Config> {
+    /// whether the [`HierarchySnapshotPlugin`] should be added
+    rollback_hierarchy: bool,
     /// phantom marker for ggrs config
     _marker: PhantomData<C>,
 }
 
 impl<C: Config> Default for GgrsPlugin<C> {
     fn default() -> Self {
-        Self { _marker: default() }
+        Self {
+            rollback_hierarchy: true,
+            _marker: default(),
+        }
+    }
+}
+
+impl<C: Config> GgrsPlugin<C> {
+    /// Do not add the [`HierarchySnapshotPlugin`], so [`Parent`] and [`Children`] are not rolled
+    /// back. Use this if your rollback entities have no hierarchy, or you manage it yourself, for
+    /// example with a [`RelationshipSnapshotPlugin`].
+    pub fn without_hierarchy_rollback(mut self) -> Self {
+        self.rollback_hierarchy = false;
+        self
     }
 }
 
 impl<C: Config> Plugin for GgrsPlugin<C> {
     fn build(&self, app: &mut App) {
-        let mut schedule = Schedule::default();
-        schedule.set_build_settings(ScheduleBuildSettings {
-            ambiguity_detection: LogLevel::Error,
-            ..default()
-        });
-
         app.init_resource::<RollbackFrameCount>()
+            .init_resource::<HighestSimulatedFrame>()
             .init_resource::<ConfirmedFrameCount>()
+            .init_resource::<SessionStartFrame>()
             .init_resource::<MaxPredictionWindow>()
             .init_resource::<RollbackOrdered>()
             .init_resource::<LocalPlayers>()
             .init_resource::<FixedTimestepData>()
-            .add_schedule(GgrsSchedule, schedule)
+            .init_resource::<RollbackTimingSettings>()
+            .init_resource::<RollbackTimeSync>()
+            .init_resource::<GgrsNetworkStats>()
+            .add_event::<FramesSkipped>()
+            .add_event::<GgrsSessionEvent<C>>()
+            .add_state::<GgrsSessionState>()
+            .init_resource::<RollbackTimestepFraction>()
+            // Plugins added earlier may already have added systems to the GgrsSchedule
+            .edit_schedule(GgrsSchedule, |schedule| {
+                schedule.set_build_settings(ScheduleBuildSettings {
+                    ambiguity_detection: LogLevel::Error,
+                    ..default()
+                });
+            })
             .add_schedule(ReadInputs, Schedule::new())
-            .add_systems(PreUpdate, schedule_systems::run_ggrs_schedules::<C>)
-            .add_plugins((
-                SnapshotSetPlugin,
-                ChecksumPlugin,
-                EntitySnapshotPlugin,
-                ComponentSnapshotPlugin::<ReflectStrategy<Parent>>::default(),
-                ComponentMapEntitiesPlugin::<Parent>::default(),
-                ComponentSnapshotPlugin::<ReflectStrategy<Children>>::default(),
-                ComponentMapEntitiesPlugin::<Children>::default(),
-            ));
+            .add_schedule(RequestsHandled, Schedule::new())
+            .add_systems(
+                PreUpdate,
+                schedule_systems::run_ggrs_schedules::<C>.in_set(GgrsUpdateSet),
+            )
+            .add_systems(
+                PreUpdate,
+                ChecksumReportPlugin::report_desyncs::<C>
+                    .after(GgrsUpdateSet)
+                    .run_if(resource_exists::<ChecksumHistory>()),
+            )
+            .add_systems(
+                LoadWorld,
+                RollbackOrdered::reset_spawn_sequence.in_set(LoadWorldSet::Entity),
+            )
+            .add_systems(
+                SaveWorld,
+                RollbackOrdered::prune.after(SaveWorldSet::Snapshot),
+            )
+            .add_plugins((SnapshotSetPlugin, ChecksumPlugin, EntitySnapshotPlugin));
+
+        if self.rollback_hierarchy {
+            app.add_plugins(HierarchySnapshotPlugin);
+        }
     }
 }
 
@@ -249,14 +500,64 @@ pub trait GgrsApp {
     where
         Type: Resource + Reflect + FromWorld;
 
-    /// Set the frequency that game updates should be performed at.
+    /// Registers a component type for saving and loading from the world. This uses [`Copy`]
+    /// based snapshots for rollback, storing only the components changed since the
+    /// previous snapshot. See [`DeltaComponentSnapshotPlugin`].
+    fn rollback_component_with_delta_copy<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Copy;
+
+    /// Registers a component type for saving and loading from the world. This uses [`Clone`]
+    /// based snapshots for rollback, storing only the components changed since the
+    /// previous snapshot. See [`DeltaComponentSnapshotPlugin`].
+    fn rollback_component_with_delta_clone<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Clone;
+
+    /// Registers a component type for saving and loading from the world. This uses
+    /// [`reflection`](`Reflect`) based snapshots for rollback, storing only the components
+    /// changed since the previous snapshot. See [`DeltaComponentSnapshotPlugin`].
+    fn rollback_component_with_delta_reflect<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Reflect + FromWorld;
+
+    /// Registers a component type for saving and loading from the world. This uses [`Copy`]
+    /// based snapshots for rollback, and only restores components whose value differs,
+    /// preserving change detection. See [`TrackedComponentSnapshotPlugin`].
+    fn rollback_component_with_tracked_copy<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Copy + PartialEq;
+
+    /// Registers a component type for saving and loading from the world. This uses [`Clone`]
+    /// based snapshots for rollback, and only restores components whose value differs,
+    /// preserving change detection. See [`TrackedComponentSnapshotPlugin`].
+    fn rollback_component_with_tracked_clone<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Clone + PartialEq;
+
+    /// Set the frequency that game updates should be performed at. This can also be changed
+    /// at runtime through the [`RollbackTimingSettings`].
     fn set_rollback_schedule_fps(&mut self, fps: usize) -> &mut Self;
 
+    /// Set how the rollback simulation slows down when this peer is ahead of its remote peers.
+    /// By default, [`RunSlowTimeSync`] is used.
+    fn set_time_sync_policy(&mut self, policy: impl TimeSyncPolicy) -> &mut Self;
+
+    /// Set how [`Rollback`] entities are identified across peers when generating checksums.
+    /// See [`RollbackIdScheme`].
+    fn set_rollback_id_scheme(&mut self, scheme: RollbackIdScheme) -> &mut Self;
+
     /// Adds a component type to the checksum generation pipeline using [`Hash`].
     fn checksum_component_with_hash<Type>(&mut self) -> &mut Self
     where
         Type: Component + Hash;
 
+    /// Maintain a [`RolledBack`] marker for a component type registered for rollback, recording
+    /// when it was last written by loading a snapshot.
+    fn track_rolled_back<Type>(&mut self) -> &mut Self
+    where
+        Type: Component;
+
     /// Updates a component after rollback using [`MapEntities`].
     fn update_component_with_map_entities<Type>(&mut self) -> &mut Self
     where
@@ -271,12 +572,48 @@ pub trait GgrsApp {
     fn update_resource_with_map_entities<Type>(&mut self) -> &mut Self
     where
         Type: Resource + MapEntities;
+
+    /// Registers an event type which can be sent and read within the [`GgrsSchedule`] using
+    /// [`RollbackEventWriter`] and [`RollbackEventReader`]. These events are rolled back using
+    /// [`Clone`] based snapshots.
+    fn add_rollback_event<Type>(&mut self) -> &mut Self
+    where
+        Type: Clone + Send + Sync + 'static;
+
+    /// Registers a side effect type which can be queued within the [`GgrsSchedule`] using
+    /// [`ConfirmedEffectWriter`], and is dispatched as a [`ConfirmedEffect`] event once the frame
+    /// it was queued in has been confirmed.
+    fn add_confirmed_effect<Type>(&mut self) -> &mut Self
+    where
+        Type: Clone + Send + Sync + 'static;
+
+    /// Registers a keyed effect type which can be declared within the [`GgrsSchedule`] using
+    /// [`PredictedEffectWriter`]. [`PredictedEffectEvent`]s are sent as soon as effects are
+    /// declared, and again if a rollback removes them.
+    fn add_predicted_effect<Type>(&mut self) -> &mut Self
+    where
+        Type: Clone + Eq + Hash + Send + Sync + 'static;
 }
 
 impl GgrsApp for App {
     fn set_rollback_schedule_fps(&mut self, fps: usize) -> &mut Self {
         self.world
-            .insert_resource(FixedTimestepData { fps, ..default() });
+            .get_resource_or_insert_with::<RollbackTimingSettings>(default)
+            .fps = fps;
+
+        self
+    }
+
+    fn set_time_sync_policy(&mut self, policy: impl TimeSyncPolicy) -> &mut Self {
+        self.world.insert_resource(RollbackTimeSync::new(policy));
+
+        self
+    }
+
+    fn set_rollback_id_scheme(&mut self, scheme: RollbackIdScheme) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with::<RollbackOrdered>(default)
+            .set_id_scheme(scheme);
 
         self
     }
@@ -295,6 +632,41 @@ impl GgrsApp for App {
         self.add_plugins(ResourceSnapshotPlugin::<ReflectStrategy<Type>>::default())
     }
 
+    fn rollback_component_with_delta_copy<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Copy,
+    {
+        self.add_plugins(DeltaComponentSnapshotPlugin::<CopyStrategy<Type>>::default())
+    }
+
+    fn rollback_component_with_delta_clone<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Clone,
+    {
+        self.add_plugins(DeltaComponentSnapshotPlugin::<CloneStrategy<Type>>::default())
+    }
+
+    fn rollback_component_with_delta_reflect<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Reflect + FromWorld,
+    {
+        self.add_plugins(DeltaComponentSnapshotPlugin::<ReflectStrategy<Type>>::default())
+    }
+
+    fn rollback_component_with_tracked_copy<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Copy + PartialEq,
+    {
+        self.add_plugins(TrackedComponentSnapshotPlugin::<CopyStrategy<Type>>::default())
+    }
+
+    fn rollback_component_with_tracked_clone<Type>(&mut self) -> &mut Self
+    where
+        Type: Component + Clone + PartialEq,
+    {
+        self.add_plugins(TrackedComponentSnapshotPlugin::<CloneStrategy<Type>>::default())
+    }
+
     fn rollback_component_with_copy<Type>(&mut self) -> &mut Self
     where
         Type: Component + Copy,
@@ -350,4 +722,32 @@ impl GgrsApp for App {
     {
         self.add_plugins(ResourceMapEntitiesPlugin::<Type>::default())
     }
+
+    fn track_rolled_back<Type>(&mut self) -> &mut Self
+    where
+        Type: Component,
+    {
+        self.init_resource::<TrackRolledBack<Type>>()
+    }
+
+    fn add_rollback_event<Type>(&mut self) -> &mut Self
+    where
+        Type: Clone + Send + Sync + 'static,
+    {
+        self.add_plugins(RollbackEventPlugin::<Type>::default())
+    }
+
+    fn add_confirmed_effect<Type>(&mut self) -> &mut Self
+    where
+        Type: Clone + Send + Sync + 'static,
+    {
+        self.add_plugins(ConfirmedEffectPlugin::<Type>::default())
+    }
+
+    fn add_predicted_effect<Type>(&mut self) -> &mut Self
+    where
+        Type: Clone + Eq + Hash + Send + Sync + 'static,
+    {
+        self.add_plugins(PredictedEffectPlugin::<Type>::default())
+    }
 }
@@ -0,0 +1,134 @@
+use bevy::{prelude::*, utils::HashMap};
+use ggrs::{NetworkStats, PlayerHandle};
+use std::collections::VecDeque;
+
+/// A [`Resource`] holding the [`NetworkStats`] of the current [`Session`](`crate::Session`),
+/// updated by the [`GgrsPlugin`](`crate::GgrsPlugin`) every update, so they can be read without
+/// borrowing the [`Session`](`crate::Session`).
+///
+/// A [`P2PSession`](`ggrs::P2PSession`) reports stats for every remote player and spectator
+/// handle, while a [`SpectatorSession`](`ggrs::SpectatorSession`) reports stats for its
+/// [`host`](`GgrsNetworkStats::host`). The most recent stats are kept as a history, to smooth
+/// or plot them.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, GgrsNetworkStats};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// // Keep 5 seconds of history
+/// app.insert_resource(GgrsNetworkStats::new(300));
+///
+/// fn show_ping(stats: Res<GgrsNetworkStats>) {
+///     for (handle, _) in stats.iter() {
+///         let pings = stats.history(handle).map(|stats| stats.ping).collect::<Vec<_>>();
+///         let average = pings.iter().sum::<u128>() / pings.len().max(1) as u128;
+///
+///         info!("player {handle}: {average}ms");
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct GgrsNetworkStats {
+    stats: HashMap<PlayerHandle, NetworkStats>,
+    host: Option<NetworkStats>,
+    history: HashMap<PlayerHandle, VecDeque<NetworkStats>>,
+    host_history: VecDeque<NetworkStats>,
+    history_length: usize,
+}
+
+impl Default for GgrsNetworkStats {
+    fn default() -> Self {
+        Self::new(120)
+    }
+}
+
+impl GgrsNetworkStats {
+    /// Create an empty [`GgrsNetworkStats`] which keeps the provided number of updates as history.
+    pub fn new(history_length: usize) -> Self {
+        Self {
+            stats: default(),
+            host: None,
+            history: default(),
+            host_history: default(),
+            history_length,
+        }
+    }
+
+    /// The most recent stats for the provided handle, if available.
+    pub fn get(&self, handle: PlayerHandle) -> Option<&NetworkStats> {
+        self.stats.get(&handle)
+    }
+
+    /// The most recent stats for every handle with stats available.
+    pub fn iter(&self) -> impl Iterator<Item = (PlayerHandle, &NetworkStats)> + '_ {
+        self.stats.iter().map(|(&handle, stats)| (handle, stats))
+    }
+
+    /// The most recent stats for the host of a [`SpectatorSession`](`ggrs::SpectatorSession`),
+    /// if available.
+    pub fn host(&self) -> Option<&NetworkStats> {
+        self.host.as_ref()
+    }
+
+    /// The stats for the provided handle over recent updates, from oldest to newest.
+    pub fn history(&self, handle: PlayerHandle) -> impl Iterator<Item = &NetworkStats> + '_ {
+        self.history.get(&handle).into_iter().flatten()
+    }
+
+    /// The stats for the host of a [`SpectatorSession`](`ggrs::SpectatorSession`) over recent
+    /// updates, from oldest to newest.
+    pub fn host_history(&self) -> impl Iterator<Item = &NetworkStats> + '_ {
+        self.host_history.iter()
+    }
+
+    /// The number of updates kept as history.
+    pub fn history_length(&self) -> usize {
+        self.history_length
+    }
+
+    /// Record the stats for the provided handle for this update, or [`None`] if they are
+    /// currently unavailable.
+    pub(crate) fn set(&mut self, handle: PlayerHandle, stats: Option<NetworkStats>) {
+        let Some(stats) = stats else {
+            self.stats.remove(&handle);
+            return;
+        };
+
+        self.stats.insert(handle, stats);
+        push_limited(
+            self.history.entry(handle).or_default(),
+            stats,
+            self.history_length,
+        );
+    }
+
+    /// Record the stats for the host for this update, or [`None`] if they are currently
+    /// unavailable.
+    pub(crate) fn set_host(&mut self, stats: Option<NetworkStats>) {
+        self.host = stats;
+
+        if let Some(stats) = stats {
+            push_limited(&mut self.host_history, stats, self.history_length);
+        }
+    }
+
+    /// Remove all stats and history, such as when the [`Session`](`crate::Session`) ends.
+    pub(crate) fn clear(&mut self) {
+        self.stats.clear();
+        self.host = None;
+        self.history.clear();
+        self.host_history.clear();
+    }
+}
+
+fn push_limited(history: &mut VecDeque<NetworkStats>, stats: NetworkStats, length: usize) {
+    history.push_back(stats);
+
+    while history.len() > length {
+        history.pop_front();
+    }
+}
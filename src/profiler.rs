@@ -0,0 +1,216 @@
+use bevy::{
+    ecs::system::{CombinatorSystem, Combine},
+    prelude::*,
+    utils::{Duration, HashMap, Instant},
+};
+use std::{
+    borrow::Cow,
+    sync::{Mutex, PoisonError},
+};
+
+/// The accumulated execution time of a system, or of the whole
+/// [`GgrsSchedule`](`crate::GgrsSchedule`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProfileTiming {
+    /// The number of times this was run.
+    pub runs: u32,
+    /// The total time spent running.
+    pub total: Duration,
+}
+
+impl ProfileTiming {
+    /// The average time spent per run, if this was run at all.
+    pub fn average(&self) -> Option<Duration> {
+        (self.runs > 0).then(|| self.total / self.runs)
+    }
+
+    fn record(&mut self, elapsed: Duration) {
+        self.runs += 1;
+        self.total += elapsed;
+    }
+}
+
+/// The execution time of a system in the [`GgrsSchedule`](`crate::GgrsSchedule`), split between
+/// frames simulated for the first time and frames resimulated after a rollback.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SystemProfile {
+    pub name: Cow<'static, str>,
+    pub first_simulation: ProfileTiming,
+    pub resimulation: ProfileTiming,
+}
+
+impl SystemProfile {
+    fn new(name: Cow<'static, str>) -> Self {
+        Self {
+            name,
+            first_simulation: default(),
+            resimulation: default(),
+        }
+    }
+
+    /// The total time spent running, across simulation and resimulation.
+    pub fn total(&self) -> Duration {
+        self.first_simulation.total + self.resimulation.total
+    }
+
+    fn record(&mut self, elapsed: Duration, resimulating: bool) {
+        if resimulating {
+            self.resimulation.record(elapsed);
+        } else {
+            self.first_simulation.record(elapsed);
+        }
+    }
+}
+
+/// A [`Resource`] accumulating the execution time of the [`GgrsSchedule`](`crate::GgrsSchedule`)
+/// and of every system wrapped with [`profiled`], added by the [`GgrsProfilerPlugin`].
+#[derive(Resource)]
+pub struct GgrsProfiler {
+    resimulating: bool,
+    schedule: SystemProfile,
+    // Profiled systems only read this resource, so they can still run in parallel
+    systems: Mutex<HashMap<Cow<'static, str>, SystemProfile>>,
+}
+
+impl Default for GgrsProfiler {
+    fn default() -> Self {
+        Self {
+            resimulating: false,
+            schedule: SystemProfile::new("GgrsSchedule".into()),
+            systems: default(),
+        }
+    }
+}
+
+impl GgrsProfiler {
+    /// Returns `true` if the frame currently being simulated was already simulated before.
+    pub fn is_resimulating(&self) -> bool {
+        self.resimulating
+    }
+
+    /// The execution time of the whole [`GgrsSchedule`](`crate::GgrsSchedule`).
+    pub fn schedule(&self) -> &SystemProfile {
+        &self.schedule
+    }
+
+    /// The execution time of every [`profiled`] system, starting with the most expensive.
+    pub fn report(&self) -> Vec<SystemProfile> {
+        let systems = self.systems.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut report = systems.values().cloned().collect::<Vec<_>>();
+        report.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.name.cmp(&b.name)));
+
+        report
+    }
+
+    /// Clear all accumulated execution times.
+    pub fn reset(&mut self) {
+        self.schedule = SystemProfile::new(self.schedule.name.clone());
+        self.systems
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Called before the [`GgrsSchedule`](`crate::GgrsSchedule`) simulates a frame.
+    pub(crate) fn begin_frame(&mut self, resimulating: bool) {
+        self.resimulating = resimulating;
+    }
+
+    /// Called after the [`GgrsSchedule`](`crate::GgrsSchedule`) ran for the provided duration.
+    pub(crate) fn end_frame(&mut self, elapsed: Duration) {
+        self.schedule.record(elapsed, self.resimulating);
+    }
+
+    fn record(&self, name: &Cow<'static, str>, elapsed: Duration) {
+        let mut systems = self.systems.lock().unwrap_or_else(PoisonError::into_inner);
+
+        systems
+            .entry(name.clone())
+            .or_insert_with(|| SystemProfile::new(name.clone()))
+            .record(elapsed, self.resimulating);
+    }
+}
+
+struct Profile;
+
+impl<A, B> Combine<A, B> for Profile
+where
+    A: System<In = (), Out = ()>,
+    B: System<In = Duration, Out = ()>,
+{
+    type In = ();
+    type Out = ();
+
+    fn combine(
+        input: Self::In,
+        a: impl FnOnce(A::In) -> A::Out,
+        b: impl FnOnce(B::In) -> B::Out,
+    ) -> Self::Out {
+        let start = Instant::now();
+        a(input);
+        b(start.elapsed());
+    }
+}
+
+/// Wraps a system so its execution time is recorded in the [`GgrsProfiler`], if the
+/// [`GgrsProfilerPlugin`] was added.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, profiled};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// fn move_players() {}
+///
+/// app.add_systems(GgrsSchedule, profiled(move_players));
+/// # }
+/// ```
+pub fn profiled<M>(system: impl IntoSystem<(), (), M>) -> impl System<In = (), Out = ()> {
+    let system = IntoSystem::into_system(system);
+    let name = system.name();
+
+    let record = {
+        let name = name.clone();
+        move |In(elapsed): In<Duration>, profiler: Option<Res<GgrsProfiler>>| {
+            if let Some(profiler) = profiler {
+                profiler.record(&name, elapsed);
+            }
+        }
+    };
+
+    CombinatorSystem::<Profile, _, _>::new(system, IntoSystem::into_system(record), name)
+}
+
+/// A [`Plugin`] which measures the execution time of the [`GgrsSchedule`](`crate::GgrsSchedule`)
+/// and of every system wrapped with [`profiled`], distinguishing frames simulated for the first
+/// time from frames resimulated after a rollback. The results are available from the
+/// [`GgrsProfiler`] resource.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, profiled, GgrsProfiler, GgrsProfilerPlugin};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// # fn move_players() {}
+/// app.add_plugins(GgrsProfilerPlugin)
+///     .add_systems(GgrsSchedule, profiled(move_players));
+///
+/// fn log_slowest_systems(profiler: Res<GgrsProfiler>) {
+///     for system in profiler.report().iter().take(3) {
+///         info!("{}: {:?} resimulating", system.name, system.resimulation.total);
+///     }
+/// }
+/// # }
+/// ```
+pub struct GgrsProfilerPlugin;
+
+impl Plugin for GgrsProfilerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GgrsProfiler>();
+    }
+}
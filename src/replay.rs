@@ -0,0 +1,308 @@
+use bevy::prelude::*;
+use bytemuck::Zeroable;
+use ggrs::{Config, GGRSRequest, InputStatus};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+const REPLAY_MAGIC: &[u8; 8] = b"GGRSRPLY";
+const REPLAY_VERSION: u32 = 1;
+
+/// The largest number of players accepted when reading a replay.
+const REPLAY_MAX_PLAYERS: usize = 64;
+
+/// The largest number of frames preallocated when reading a replay. Longer replays are still
+/// read, but the header of a corrupt file cannot cause a huge allocation.
+const REPLAY_MAX_PREALLOCATED_FRAMES: usize = 1 << 16;
+
+/// A [`Resource`] which records every set of [`PlayerInputs`](`crate::PlayerInputs`) delivered
+/// to the [`GgrsSchedule`](`crate::GgrsSchedule`), indexed by [`RollbackFrameCount`](`crate::RollbackFrameCount`).
+///
+/// Frames are recorded as counted by the [`RollbackFrameCount`](`crate::RollbackFrameCount`),
+/// including the [`SessionStartFrame`](`crate::SessionStartFrame`), and a [`ReplaySession`]
+/// plays every set of inputs back on the frame it was recorded on.
+///
+/// Recording starts as soon as this resource is inserted. When a frame is resimulated after a
+/// rollback, the recording is truncated to that frame, so the final recording always reflects the
+/// most recent simulation of each frame. A finished recording can be played back with a
+/// [`ReplaySession`].
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, InputRecording};
+/// #
+/// # type MyInputType = u8;
+/// #
+/// # fn start(session: Session<GgrsConfig<MyInputType>>) {
+/// # let mut app = App::new();
+/// // Record all inputs of the session
+/// app.init_resource::<InputRecording<GgrsConfig<MyInputType>>>();
+/// # }
+/// ```
+#[derive(Resource)]
+pub struct InputRecording<C: Config> {
+    /// The frame of the first recorded set of inputs.
+    start_frame: i32,
+    /// Recorded inputs, where index `i` corresponds to frame `start_frame + i`.
+    frames: Vec<Vec<(C::Input, InputStatus)>>,
+}
+
+impl<C: Config> Default for InputRecording<C> {
+    fn default() -> Self {
+        Self {
+            start_frame: 0,
+            frames: Vec::new(),
+        }
+    }
+}
+
+impl<C: Config> InputRecording<C> {
+    /// Records the inputs for the provided frame, discarding any inputs previously recorded
+    /// for this or any later frame.
+    pub fn record(&mut self, frame: i32, inputs: &[(C::Input, InputStatus)]) -> &mut Self {
+        if self.frames.is_empty() || frame < self.start_frame {
+            self.frames.clear();
+            self.start_frame = frame;
+        }
+
+        let index = (frame - self.start_frame) as usize;
+
+        if index > self.frames.len() {
+            warn!(
+                "Input recording skipped from frame {} to {frame}, restarting the recording",
+                self.end_frame()
+            );
+            self.frames.clear();
+            self.start_frame = frame;
+        } else {
+            self.frames.truncate(index);
+        }
+
+        self.frames.push(inputs.to_vec());
+
+        self
+    }
+
+    /// The first frame contained in this recording.
+    pub const fn start_frame(&self) -> i32 {
+        self.start_frame
+    }
+
+    /// The frame after the last frame contained in this recording.
+    pub fn end_frame(&self) -> i32 {
+        self.start_frame + self.frames.len() as i32
+    }
+
+    /// The number of recorded frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if no frames have been recorded, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The number of players in this recording.
+    pub fn num_players(&self) -> usize {
+        self.frames.first().map(Vec::len).unwrap_or_default()
+    }
+
+    /// Get the inputs recorded for a particular frame, if any.
+    pub fn get(&self, frame: i32) -> Option<&[(C::Input, InputStatus)]> {
+        let index = usize::try_from(frame - self.start_frame).ok()?;
+        self.frames.get(index).map(Vec::as_slice)
+    }
+
+    /// Iterate over all recorded frames as `(frame, inputs)`.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &[(C::Input, InputStatus)])> + '_ {
+        self.frames
+            .iter()
+            .enumerate()
+            .map(|(index, inputs)| (self.start_frame + index as i32, inputs.as_slice()))
+    }
+
+    /// Write this recording in the binary replay format.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let num_players = self.num_players();
+        if num_players == 0 && !self.frames.is_empty() {
+            return Err(invalid_data("recorded frames have no players"));
+        }
+
+        writer.write_all(REPLAY_MAGIC)?;
+        writer.write_all(&REPLAY_VERSION.to_le_bytes())?;
+        writer.write_all(&(std::mem::size_of::<C::Input>() as u32).to_le_bytes())?;
+        writer.write_all(&(num_players as u32).to_le_bytes())?;
+        writer.write_all(&self.start_frame.to_le_bytes())?;
+        writer.write_all(&(self.frames.len() as u32).to_le_bytes())?;
+
+        for inputs in &self.frames {
+            if inputs.len() != num_players {
+                return Err(invalid_data("player count changed during the recording"));
+            }
+
+            for (input, status) in inputs {
+                writer.write_all(bytemuck::bytes_of(input))?;
+                writer.write_all(&[status_to_byte(*status)])?;
+            }
+        }
+
+        writer.flush()
+    }
+
+    /// Read a recording in the binary replay format.
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != REPLAY_MAGIC {
+            return Err(invalid_data("not a GGRS replay"));
+        }
+
+        let version = read_u32(&mut reader)?;
+        if version != REPLAY_VERSION {
+            return Err(invalid_data("unsupported replay version"));
+        }
+
+        let input_size = read_u32(&mut reader)? as usize;
+        if input_size != std::mem::size_of::<C::Input>() {
+            return Err(invalid_data("replay input size does not match the Config"));
+        }
+
+        let num_players = read_u32(&mut reader)? as usize;
+        if num_players > REPLAY_MAX_PLAYERS {
+            return Err(invalid_data("replay has too many players"));
+        }
+
+        let start_frame = read_u32(&mut reader)? as i32;
+        let num_frames = read_u32(&mut reader)? as usize;
+
+        // Frames without players take no space, so their count could never be bounded by the input
+        if num_players == 0 && num_frames > 0 {
+            return Err(invalid_data("replay frames have no players"));
+        }
+
+        let mut frames = Vec::with_capacity(num_frames.min(REPLAY_MAX_PREALLOCATED_FRAMES));
+
+        for _ in 0..num_frames {
+            let mut inputs = Vec::with_capacity(num_players);
+
+            for _ in 0..num_players {
+                let mut input = C::Input::zeroed();
+                reader.read_exact(bytemuck::bytes_of_mut(&mut input))?;
+
+                let mut status = [0];
+                reader.read_exact(&mut status)?;
+
+                inputs.push((input, status_from_byte(status[0])?));
+            }
+
+            frames.push(inputs);
+        }
+
+        Ok(Self {
+            start_frame,
+            frames,
+        })
+    }
+
+    /// Save this recording to a file in the binary replay format.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_to(BufWriter::new(File::create(path)?))
+    }
+
+    /// Load a recording from a file in the binary replay format.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+/// A session which plays back an [`InputRecording`] through the regular
+/// [`GgrsSchedule`](`crate::GgrsSchedule`), one recorded frame per step.
+///
+/// Replays never roll back, so no snapshots are required to play them. The
+/// [`RollbackFrameCount`](`crate::RollbackFrameCount`) is kept in sync with the recording,
+/// so game systems behave exactly as they did when the recording was made.
+pub struct ReplaySession<C: Config> {
+    recording: InputRecording<C>,
+    cursor: usize,
+}
+
+impl<C: Config> ReplaySession<C> {
+    /// Create a new [`ReplaySession`] which will play back the provided [`InputRecording`].
+    pub fn new(recording: InputRecording<C>) -> Self {
+        Self {
+            recording,
+            cursor: 0,
+        }
+    }
+
+    /// Create a new [`ReplaySession`] from a replay file.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        InputRecording::load(path).map(Self::new)
+    }
+
+    /// The [`InputRecording`] being played back.
+    pub fn recording(&self) -> &InputRecording<C> {
+        &self.recording
+    }
+
+    /// The number of players in the recording.
+    pub fn num_players(&self) -> usize {
+        self.recording.num_players()
+    }
+
+    /// The frame that will be simulated by the next call to [`advance_frame`](`ReplaySession::advance_frame`).
+    pub fn next_frame(&self) -> i32 {
+        self.recording.start_frame() + self.cursor as i32
+    }
+
+    /// Returns `true` if every recorded frame has been played back.
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.recording.len()
+    }
+
+    /// Restart the playback from the first recorded frame.
+    pub fn restart(&mut self) -> &mut Self {
+        self.cursor = 0;
+        self
+    }
+
+    /// Produce the requests required to simulate the next recorded frame,
+    /// or [`None`] if the replay has finished.
+    pub fn advance_frame(&mut self) -> Option<Vec<GGRSRequest<C>>> {
+        let inputs = self.recording.frames.get(self.cursor)?.clone();
+        self.cursor += 1;
+
+        Some(vec![GGRSRequest::AdvanceFrame { inputs }])
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn status_to_byte(status: InputStatus) -> u8 {
+    match status {
+        InputStatus::Confirmed => 0,
+        InputStatus::Predicted => 1,
+        InputStatus::Disconnected => 2,
+    }
+}
+
+fn status_from_byte(byte: u8) -> io::Result<InputStatus> {
+    match byte {
+        0 => Ok(InputStatus::Confirmed),
+        1 => Ok(InputStatus::Predicted),
+        2 => Ok(InputStatus::Disconnected),
+        _ => Err(invalid_data("invalid input status")),
+    }
+}
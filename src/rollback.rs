@@ -4,6 +4,8 @@ use bevy::{
     prelude::*,
 };
 
+use crate::{ConfirmedFrameCount, RollbackFrameCount};
+
 /// This component flags an entity as being included in the rollback save/load schedule with GGRS.
 ///
 /// You must use the `AddRollbackCommand` when spawning an entity to add this component. Alternatively,
@@ -27,9 +29,14 @@ impl EntityCommand for AddRollbackCommand {
 
         world.entity_mut(id).insert(rollback);
 
+        let frame = world
+            .get_resource::<RollbackFrameCount>()
+            .map(|frame| frame.0)
+            .unwrap_or_default();
+
         world
             .get_resource_or_insert_with::<RollbackOrdered>(default)
-            .push(rollback);
+            .push(rollback, frame);
     }
 }
 
@@ -53,49 +60,242 @@ impl<'w, 's, 'a> AddRollbackCommandExtension for EntityCommands<'w, 's, 'a> {
     }
 }
 
+/// Determines how [`RollbackOrdered::id`] identifies [`Rollback`] entities across peers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RollbackIdScheme {
+    /// Ids follow the order in which [`Rollback`] flags were added, see
+    /// [`RollbackOrdered::index`]. This only matches across peers if every peer adds them in
+    /// exactly the same order.
+    #[default]
+    RegistrationOrder,
+    /// Ids are derived from the frame a [`Rollback`] was added in, and the order in which it was
+    /// added during that frame. Entities without [`Rollback`] (UI, audio, etc.) may be spawned
+    /// differently on each peer, but all [`Rollback`] entities must be added either within the
+    /// [`GgrsSchedule`](`crate::GgrsSchedule`), or identically on every peer before the
+    /// [`Session`](`crate::Session`) starts.
+    SpawnOrder,
+}
+
+/// How a single [`Rollback`] is registered within [`RollbackOrdered`], which allows it to be
+/// restored in another [`World`] without changing its [`id`](`RollbackOrdered::id`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RollbackRegistration {
+    /// The index provided by [`RollbackOrdered::index`].
+    pub index: usize,
+    /// The id used by [`RollbackIdScheme::SpawnOrder`].
+    pub spawn_id: u64,
+}
+
 /// A [`Resource`] which provides methods for stable ordering of [`Rollback`] flags.
+///
+/// Entries for [`Rollback`] entities which were despawned before the [`ConfirmedFrameCount`] are
+/// pruned, as they can no longer be restored by a rollback.
 #[derive(Resource, Default)]
 pub struct RollbackOrdered {
-    order: HashMap<Rollback, usize>,
     sorted: Vec<Rollback>,
+    /// The index each [`Rollback`] was registered with, which is never reassigned.
+    index: HashMap<Rollback, usize>,
+    spawned: HashMap<Rollback, u64>,
+    /// The [`Rollback`] of every living [`Entity`], to identify those which are despawned.
+    entities: HashMap<Entity, Rollback>,
+    /// The frame each missing [`Rollback`] was first found to be despawned in.
+    despawned: HashMap<Rollback, i32>,
+    /// The index assigned to the next registered [`Rollback`].
+    next: usize,
+    /// The frame currently being spawned in, and the number of [`Rollback`] flags added during it.
+    sequence: Option<(i32, u32)>,
+    scheme: RollbackIdScheme,
 }
 
 impl RollbackOrdered {
-    /// Register a new [`Rollback`] for explicit ordering.
-    fn push(&mut self, rollback: Rollback) -> &mut Self {
-        // sorted is already sorted, and rollback should be inserted at the back most of the time
-        self.sorted.push(rollback);
-
-        // If this is the first item we can return early
-        if self.sorted.len() == 1 {
-            self.order.insert(rollback, 0);
+    /// Register a new [`Rollback`] added during the provided frame for explicit ordering.
+    fn push(&mut self, rollback: Rollback, frame: i32) -> &mut Self {
+        // Restoring a despawned entity retains its original registration
+        if self.index.contains_key(&rollback) {
             return self;
         }
 
-        // Iterate from the back of sorted, swapping and updating order until stable
-        for index in (1..self.sorted.len()).rev() {
-            if self.sorted[index] >= self.sorted[index - 1] {
-                self.order.insert(self.sorted[index], index);
-                break;
-            }
+        let sequence = match self.sequence {
+            Some((current, sequence)) if current == frame => sequence,
+            _ => 0,
+        };
+
+        self.sequence = Some((frame, sequence + 1));
+        self.spawned
+            .insert(rollback, ((frame as u32 as u64) << 32) | sequence as u64);
+
+        self.index.insert(rollback, self.next);
+        self.next += 1;
 
-            self.sorted.swap(index, index - 1);
-            self.order.insert(self.sorted[index], index);
+        // sorted is already sorted, and rollback should be inserted at the back most of the time
+        let index = self.sorted.partition_point(|&other| other < rollback);
+        self.sorted.insert(index, rollback);
+
+        self
+    }
+
+    /// Register a [`Rollback`] with a [`RollbackRegistration`] taken from another [`World`].
+    pub(crate) fn restore(
+        &mut self,
+        rollback: Rollback,
+        registration: RollbackRegistration,
+    ) -> &mut Self {
+        self.index.insert(rollback, registration.index);
+        self.spawned.insert(rollback, registration.spawn_id);
+        self.next = self.next.max(registration.index + 1);
+
+        if let Err(index) = self.sorted.binary_search(&rollback) {
+            self.sorted.insert(index, rollback);
         }
 
         self
     }
 
-    /// Iterate over all [`Rollback`] markers ever registered, even if they have since been deleted.
+    /// Returns how the provided [`Rollback`] is registered, or [`None`] if it was never
+    /// registered or has since been pruned.
+    pub fn registration(&self, rollback: Rollback) -> Option<RollbackRegistration> {
+        Some(RollbackRegistration {
+            index: *self.index.get(&rollback)?,
+            spawn_id: *self.spawned.get(&rollback)?,
+        })
+    }
+
+    /// The index which will be provided by [`index`](`RollbackOrdered::index`) for the next
+    /// registered [`Rollback`].
+    pub fn next_index(&self) -> usize {
+        self.next
+    }
+
+    /// Ensure the next registered [`Rollback`] is given an index of at least `next`, so peers
+    /// resuming from a transferred state continue to assign the same indices.
+    pub(crate) fn reserve_index(&mut self, next: usize) -> &mut Self {
+        self.next = self.next.max(next);
+        self
+    }
+
+    /// Iterate over all [`Rollback`] markers registered, sorted by [`Rollback`]. This includes
+    /// deleted markers which may still be restored by a rollback.
     pub fn iter_sorted(&self) -> impl Iterator<Item = Rollback> + '_ {
         self.sorted.iter().copied()
     }
 
-    /// Returns a unique and order stable index for the provided [`Rollback`].
+    /// The number of [`Rollback`] markers currently registered.
+    pub fn len(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// Returns `true` if no [`Rollback`] markers are registered, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+
+    /// Returns a unique and stable index for the provided [`Rollback`], identical to
+    /// [`index`](`RollbackOrdered::index`).
+    ///
+    /// This used to be the position of the [`Rollback`] within
+    /// [`iter_sorted`](`RollbackOrdered::iter_sorted`), which would shift as entries for despawned
+    /// entities are pruned. Use [`iter_sorted`](`RollbackOrdered::iter_sorted`) directly to
+    /// order [`Rollback`] flags.
+    #[deprecated(note = "use `RollbackOrdered::index` instead")]
     pub fn order(&self, rollback: Rollback) -> usize {
-        self.order
+        self.index(rollback)
+    }
+
+    /// Returns a unique index for the provided [`Rollback`], assigned in the order [`Rollback`]
+    /// flags were added. This is never reassigned, even when other entries are pruned.
+    pub fn index(&self, rollback: Rollback) -> usize {
+        self.index
             .get(&rollback)
             .copied()
             .expect("Rollback requested was not created using AddRollbackCommand!")
     }
+
+    /// Returns an id for the provided [`Rollback`] which is identical across peers, according
+    /// to the current [`RollbackIdScheme`].
+    pub fn id(&self, rollback: Rollback) -> u64 {
+        match self.scheme {
+            RollbackIdScheme::RegistrationOrder => self.index(rollback) as u64,
+            RollbackIdScheme::SpawnOrder => self
+                .spawned
+                .get(&rollback)
+                .copied()
+                .expect("Rollback requested was not created using AddRollbackCommand!"),
+        }
+    }
+
+    pub fn set_id_scheme(&mut self, scheme: RollbackIdScheme) -> &mut Self {
+        self.scheme = scheme;
+        self
+    }
+
+    pub const fn id_scheme(&self) -> RollbackIdScheme {
+        self.scheme
+    }
+
+    /// A [`System`] which restarts the spawn sequence after a rollback, so entities spawned while
+    /// resimulating a frame receive the same ids as when it was first simulated.
+    pub fn reset_spawn_sequence(mut rollback_ordered: ResMut<Self>) {
+        rollback_ordered.sequence = None;
+    }
+
+    /// A [`System`] which records when [`Rollback`] entities are despawned, and forgets those
+    /// which were despawned before the [`ConfirmedFrameCount`]. Only entities which gained or
+    /// lost their [`Rollback`] since this last ran are inspected.
+    pub fn prune(
+        mut rollback_ordered: ResMut<Self>,
+        frame: Res<RollbackFrameCount>,
+        confirmed_frame: Option<Res<ConfirmedFrameCount>>,
+        mut removed: RemovedComponents<Rollback>,
+        added: Query<(Entity, &Rollback), Added<Rollback>>,
+    ) {
+        let rollback_ordered = rollback_ordered.as_mut();
+
+        for entity in removed.iter() {
+            // Entities may be despawned before this ever observed them
+            let rollback = rollback_ordered
+                .entities
+                .remove(&entity)
+                .unwrap_or(Rollback::new(entity));
+
+            if rollback_ordered.index.contains_key(&rollback) {
+                rollback_ordered
+                    .despawned
+                    .entry(rollback)
+                    .or_insert(frame.0);
+            }
+        }
+
+        // A rollback may have restored a despawned entity
+        for (entity, &rollback) in added.iter() {
+            rollback_ordered.entities.insert(entity, rollback);
+            rollback_ordered.despawned.remove(&rollback);
+        }
+
+        let Some(confirmed_frame) = confirmed_frame else {
+            return;
+        };
+
+        let pruned = rollback_ordered
+            .despawned
+            .iter()
+            .filter(|(_, &despawned)| despawned < confirmed_frame.0)
+            .map(|(&rollback, _)| rollback)
+            .collect::<Vec<_>>();
+
+        if pruned.is_empty() {
+            return;
+        }
+
+        for rollback in &pruned {
+            rollback_ordered.index.remove(rollback);
+            rollback_ordered.spawned.remove(rollback);
+            rollback_ordered.despawned.remove(rollback);
+
+            if let Ok(index) = rollback_ordered.sorted.binary_search(rollback) {
+                rollback_ordered.sorted.remove(index);
+            }
+        }
+
+        trace!("Pruned {} despawned Rollback(s)", pruned.len());
+    }
 }
@@ -0,0 +1,177 @@
+use crate::{CloneStrategy, GgrsSchedule, ResourceSnapshotPlugin, RollbackFrameCount};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use std::marker::PhantomData;
+
+/// A rollback-safe alternative to [`Events`]. Every event is tagged with the
+/// [`RollbackFrameCount`] it was sent in, and the buffer is snapshot like any other
+/// rolled back [`Resource`]. Events sent during a frame can be read during the following
+/// simulated frame, regardless of how many Bevy updates or rollbacks happen in between.
+///
+/// Use [`RollbackEventWriter`] and [`RollbackEventReader`] to interact with this buffer from
+/// systems in the [`GgrsSchedule`](`crate::GgrsSchedule`), and register the event type with
+/// [`RollbackEventPlugin`] or [`GgrsApp::add_rollback_event`](`crate::GgrsApp::add_rollback_event`).
+#[derive(Resource, Clone)]
+pub struct RollbackEvents<E> {
+    events: Vec<(i32, E)>,
+}
+
+impl<E> Default for RollbackEvents<E> {
+    fn default() -> Self {
+        Self { events: Vec::new() }
+    }
+}
+
+impl<E> RollbackEvents<E> {
+    /// Send an event during the provided frame.
+    pub fn send(&mut self, frame: i32, event: E) -> &mut Self {
+        self.events.push((frame, event));
+        self
+    }
+
+    /// Iterate over all events sent during the provided frame.
+    pub fn iter_frame(&self, frame: i32) -> impl Iterator<Item = &E> + '_ {
+        self.events
+            .iter()
+            .filter(move |(sent, _)| *sent == frame)
+            .map(|(_, event)| event)
+    }
+
+    /// Discard all events sent before the provided frame.
+    pub fn discard_before(&mut self, frame: i32) -> &mut Self {
+        self.events.retain(|(sent, _)| *sent >= frame);
+        self
+    }
+
+    /// The number of buffered events.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if there are no buffered events, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Sends [`RollbackEvents`] of type `E` during the current frame.
+///
+/// NOTE: As this requires mutable access to [`RollbackEvents<E>`], systems using it must be
+/// explicitly ordered relative to systems using a [`RollbackEventReader<E>`] to satisfy the
+/// ambiguity checks of the [`GgrsSchedule`](`crate::GgrsSchedule`). The chosen order does not
+/// affect which events are read.
+#[derive(SystemParam)]
+pub struct RollbackEventWriter<'w, E: Send + Sync + 'static> {
+    events: ResMut<'w, RollbackEvents<E>>,
+    frame: Res<'w, RollbackFrameCount>,
+}
+
+impl<'w, E: Send + Sync + 'static> RollbackEventWriter<'w, E> {
+    /// Send an event, which can be read during the next simulated frame.
+    pub fn send(&mut self, event: E) {
+        let frame = self.frame.0;
+        self.events.send(frame, event);
+    }
+
+    /// Send a batch of events, which can be read during the next simulated frame.
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = E>) {
+        for event in events {
+            self.send(event);
+        }
+    }
+}
+
+/// Reads [`RollbackEvents`] of type `E` sent during the previous frame.
+#[derive(SystemParam)]
+pub struct RollbackEventReader<'w, E: Send + Sync + 'static> {
+    events: Res<'w, RollbackEvents<E>>,
+    frame: Res<'w, RollbackFrameCount>,
+}
+
+impl<'w, E: Send + Sync + 'static> RollbackEventReader<'w, E> {
+    /// Iterate over all events sent during the previous frame.
+    pub fn iter(&self) -> impl Iterator<Item = &E> + '_ {
+        self.events.iter_frame(self.frame.0 - 1)
+    }
+
+    /// Returns `true` if no events were sent during the previous frame, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+/// A [`Plugin`] which registers [`RollbackEvents`] of type `E`, snapshots them using
+/// [`Clone`], and discards events once they can no longer be read.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, RollbackEventPlugin, RollbackEventReader, RollbackEventWriter};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// #[derive(Clone)]
+/// struct Explosion(Vec3);
+///
+/// app.add_plugins(RollbackEventPlugin::<Explosion>::default());
+///
+/// fn explode(mut explosions: RollbackEventWriter<Explosion>) {
+///     explosions.send(Explosion(Vec3::ZERO));
+/// }
+///
+/// fn damage(explosions: RollbackEventReader<Explosion>) {
+///     for Explosion(position) in explosions.iter() {
+///         // ...
+///     }
+/// }
+///
+/// app.add_systems(GgrsSchedule, (explode, damage).chain());
+/// # }
+/// ```
+pub struct RollbackEventPlugin<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    _phantom: PhantomData<E>,
+}
+
+impl<E> Default for RollbackEventPlugin<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: default(),
+        }
+    }
+}
+
+impl<E> RollbackEventPlugin<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    /// A [`System`] which discards all events which can no longer be read during the current frame.
+    ///
+    /// This runs in the [`GgrsSchedule`], so the buffer stays bounded for sessions which never
+    /// save a snapshot, such as a [`SpectatorSession`](`ggrs::SpectatorSession`).
+    pub fn discard_old_events(
+        mut events: ResMut<RollbackEvents<E>>,
+        frame: Res<RollbackFrameCount>,
+    ) {
+        events.discard_before(frame.0 - 1);
+    }
+}
+
+impl<E> Plugin for RollbackEventPlugin<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RollbackEvents<E>>()
+            .add_plugins(ResourceSnapshotPlugin::<CloneStrategy<RollbackEvents<E>>>::default())
+            .add_systems(
+                GgrsSchedule,
+                // Only events which no reader or writer can observe this frame are discarded
+                Self::discard_old_events.ambiguous_with_all(),
+            );
+    }
+}
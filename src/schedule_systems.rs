@@ -1,12 +1,77 @@
 use crate::{
-    Checksum, ConfirmedFrameCount, FixedTimestepData, GgrsSchedule, LoadWorld, LocalInputs,
-    LocalPlayers, MaxPredictionWindow, PlayerInputs, ReadInputs, RollbackFrameCount, SaveWorld,
-    Session,
+    snapshot::report_sync_test_mismatches, CatchUpPolicy, Checksum, ConfirmedFrameCount,
+    FixedTimestepData, FramesSkipped, GgrsNetworkStats, GgrsProfiler, GgrsSchedule,
+    GgrsSessionEvent, GgrsSessionState, HighestSimulatedFrame, InputRecording, LoadWorld,
+    LocalInputs, LocalPlayers, MaxPredictionWindow, PlayerInputs, ReadInputs, ReplaySession,
+    RequestsHandled, RollbackFrameCount, RollbackMetrics, RollbackTimeSync,
+    RollbackTimestepFraction, RollbackTimingSettings, SaveWorld, Session, SessionStartFrame,
+};
+use bevy::{
+    prelude::*,
+    utils::{Duration, HashMap, Instant},
 };
-use bevy::{prelude::*, utils::Duration};
 use ggrs::{
-    Config, GGRSError, GGRSRequest, P2PSession, SessionState, SpectatorSession, SyncTestSession,
+    Config, GGRSError, GGRSEvent, GGRSRequest, P2PSession, PlayerHandle, SessionState,
+    SpectatorSession, SyncTestSession,
 };
+use std::{collections::VecDeque, num::NonZeroUsize};
+
+/// Local inputs waiting to be added to the [`Session`], delayed according to
+/// [`RollbackTimingSettings::input_delay`].
+#[derive(Resource)]
+pub(crate) struct DelayedLocalInputs<C: Config> {
+    queues: HashMap<PlayerHandle, VecDeque<C::Input>>,
+}
+
+impl<C: Config> Default for DelayedLocalInputs<C> {
+    fn default() -> Self {
+        Self { queues: default() }
+    }
+}
+
+impl<C: Config> DelayedLocalInputs<C> {
+    /// Queue the provided input, returning the input which should be added to the session now.
+    fn delay(&mut self, handle: PlayerHandle, input: C::Input, delay: usize) -> C::Input {
+        let queue = self.queues.entry(handle).or_default();
+
+        queue.push_back(input);
+
+        // If the delay was reduced, drop the oldest inputs
+        while queue.len() > delay + 1 {
+            queue.pop_front();
+        }
+
+        // If the delay was increased, repeat the oldest input
+        while queue.len() < delay + 1 {
+            let filler = queue.front().copied().unwrap_or(input);
+            queue.push_front(filler);
+        }
+
+        queue.pop_front().unwrap_or(input)
+    }
+}
+
+fn read_local_inputs<C: Config>(world: &mut World) -> HashMap<PlayerHandle, C::Input> {
+    world.run_schedule(ReadInputs);
+
+    let local_inputs = world.remove_resource::<LocalInputs<C>>().expect(
+        "No local player inputs found. Did you insert systems into the ReadInputs schedule?",
+    );
+
+    let delay = world.resource::<RollbackTimingSettings>().input_delay;
+
+    if delay == 0 && !world.contains_resource::<DelayedLocalInputs<C>>() {
+        return local_inputs.0;
+    }
+
+    let mut delayed = world.get_resource_or_insert_with(DelayedLocalInputs::<C>::default);
+
+    local_inputs
+        .0
+        .into_iter()
+        .map(|(handle, input)| (handle, delayed.delay(handle, input, delay)))
+        .collect()
+}
 
 pub(crate) fn run_ggrs_schedules<T: Config>(world: &mut World) {
     let mut time_data = world
@@ -18,27 +83,82 @@ pub(crate) fn run_ggrs_schedules<T: Config>(world: &mut World) {
         .expect("Time resource not found, did you remove it?")
         .delta();
 
-    let mut fps_delta = 1. / time_data.fps as f64;
-    if time_data.run_slow {
-        fps_delta *= 1.1;
-    }
+    let settings = *world.resource::<RollbackTimingSettings>();
+
+    let fps_delta = time_data.frame_time_factor / settings.fps as f64;
     time_data.accumulator = time_data.accumulator.saturating_add(delta);
 
+    let start_frame = world
+        .get_resource::<SessionStartFrame>()
+        .map(|frame| frame.0)
+        .unwrap_or_default();
+
     // no matter what, poll remotes and send responses
     if let Some(mut session) = world.get_resource_mut::<Session<T>>() {
-        match &mut *session {
+        let events = match &mut *session {
             Session::P2P(session) => {
                 session.poll_remote_clients();
+                session
+                    .events()
+                    .map(|event| forward_event(event, start_frame))
+                    .collect()
             }
             Session::Spectator(session) => {
                 session.poll_remote_clients();
+                session
+                    .events()
+                    .map(|event| forward_event(event, start_frame))
+                    .collect()
             }
-            _ => {}
+            _ => Vec::new(),
+        };
+
+        if let Some(mut sent) = world.get_resource_mut::<Events<GgrsSessionEvent<T>>>() {
+            sent.extend(events);
         }
     }
 
+    update_session_state::<T>(world);
+    update_network_stats::<T>(world);
+
+    let mut steps = 0;
+
     // if we accumulated enough time, do steps
     while time_data.accumulator.as_secs_f64() > fps_delta {
+        if let Some(max_steps) = settings
+            .max_steps_per_update
+            .map(NonZeroUsize::get)
+            .filter(|&max_steps| steps >= max_steps)
+        {
+            let frames = (time_data.accumulator.as_secs_f64() / fps_delta) as usize;
+
+            // carrying over more than a single update's worth of frames would never catch up
+            let carried = match settings.catch_up_policy {
+                CatchUpPolicy::Discard => 0,
+                CatchUpPolicy::CarryOver => max_steps,
+            };
+            let discarded = frames.saturating_sub(carried);
+
+            if discarded > 0 {
+                time_data.accumulator = time_data
+                    .accumulator
+                    .saturating_sub(Duration::from_secs_f64(fps_delta * discarded as f64));
+
+                debug!("skipping {discarded} frame(s) this update");
+
+                if let Some(mut events) = world.get_resource_mut::<Events<FramesSkipped>>() {
+                    events.send(FramesSkipped {
+                        frames: discarded,
+                        policy: settings.catch_up_policy,
+                    });
+                }
+            }
+
+            break;
+        }
+
+        steps += 1;
+
         // decrease accumulator
         time_data.accumulator = time_data
             .accumulator
@@ -49,36 +169,117 @@ pub(crate) fn run_ggrs_schedules<T: Config>(world: &mut World) {
         match session {
             Some(Session::SyncTest(s)) => run_synctest::<T>(world, s),
             Some(Session::P2P(session)) => {
+                if let Some(mut metrics) = world.get_resource_mut::<RollbackMetrics>() {
+                    metrics.frames_ahead = Some(session.frames_ahead());
+                }
+
                 // if we are ahead, run slow
-                time_data.run_slow = session.frames_ahead() > 0;
+                time_data.frame_time_factor = world
+                    .resource_mut::<RollbackTimeSync>()
+                    .frame_time_factor(session.frames_ahead(), &settings);
 
                 run_p2p(world, session);
             }
             Some(Session::Spectator(s)) => run_spectator(world, s),
+            Some(Session::Replay(s)) => run_replay(world, s),
             _ => {
                 // No session has been started yet, reset time data and snapshots
                 time_data.accumulator = Duration::ZERO;
-                time_data.run_slow = false;
+                time_data.frame_time_factor = 1.;
+
                 world.insert_resource(LocalPlayers::default());
-                world.insert_resource(RollbackFrameCount(0));
-                world.insert_resource(ConfirmedFrameCount(-1));
+                world.remove_resource::<DelayedLocalInputs<T>>();
+                world.insert_resource(RollbackFrameCount(start_frame));
+                world.insert_resource(HighestSimulatedFrame(start_frame));
+                world.insert_resource(ConfirmedFrameCount(start_frame - 1));
                 world.insert_resource(MaxPredictionWindow(8));
             }
         }
     }
 
+    let fraction = (time_data.accumulator.as_secs_f64() / fps_delta).clamp(0., 1.);
+    world
+        .get_resource_or_insert_with(RollbackTimestepFraction::default)
+        .set_if_neq(RollbackTimestepFraction(fraction as f32));
+
     world.insert_resource(time_data);
 }
 
+/// Record the [`GgrsNetworkStats`] of the current [`Session`].
+fn update_network_stats<C: Config>(world: &mut World) {
+    if !world.contains_resource::<GgrsNetworkStats>() {
+        return;
+    }
+
+    let (stats, host) = match world.get_resource::<Session<C>>() {
+        Some(Session::P2P(session)) => {
+            let stats = session
+                .remote_player_handles()
+                .into_iter()
+                .chain(session.spectator_handles())
+                .map(|handle| (handle, session.network_stats(handle).ok()))
+                .collect::<Vec<_>>();
+
+            (stats, None)
+        }
+        Some(Session::Spectator(session)) => (Vec::new(), session.network_stats().ok()),
+        // without remote peers, the stats of a previous session must not be kept
+        Some(Session::SyncTest(_)) | Some(Session::Replay(_)) | None => {
+            world.resource_mut::<GgrsNetworkStats>().clear();
+            return;
+        }
+    };
+
+    let mut network_stats = world.resource_mut::<GgrsNetworkStats>();
+
+    for (handle, stats) in stats {
+        network_stats.set(handle, stats);
+    }
+
+    network_stats.set_host(host);
+}
+
+/// Wrap a [`GGRSEvent`] to be sent as a [`GgrsSessionEvent`], offsetting its frame by the
+/// [`SessionStartFrame`], as GGRS counts frames from the start of the session.
+fn forward_event<C: Config>(mut event: GGRSEvent<C>, start_frame: i32) -> GgrsSessionEvent<C> {
+    if let GGRSEvent::DesyncDetected { frame, .. } = &mut event {
+        *frame += start_frame;
+    }
+
+    GgrsSessionEvent(event)
+}
+
+/// Transition the [`GgrsSessionState`] to match the current [`Session`], if required.
+fn update_session_state<C: Config>(world: &mut World) {
+    let Some(current) = world
+        .get_resource::<State<GgrsSessionState>>()
+        .map(|state| *state.get())
+    else {
+        return;
+    };
+
+    let next = match world.get_resource::<Session<C>>() {
+        Some(Session::P2P(session)) => session.current_state().into(),
+        Some(Session::Spectator(session)) => session.current_state().into(),
+        Some(Session::SyncTest(_)) | Some(Session::Replay(_)) => GgrsSessionState::Running,
+        None if current == GgrsSessionState::NoSession => GgrsSessionState::NoSession,
+        None => GgrsSessionState::Disconnected,
+    };
+
+    if next != current {
+        debug!("session state changing from {current:?} to {next:?}");
+        world
+            .resource_mut::<NextState<GgrsSessionState>>()
+            .set(next);
+    }
+}
+
 pub(crate) fn run_synctest<C: Config>(world: &mut World, mut sess: SyncTestSession<C>) {
     world.insert_resource(LocalPlayers((0..sess.num_players()).collect()));
 
     // read local player inputs and register them in the session
-    world.run_schedule(ReadInputs);
-    let local_inputs = world.remove_resource::<LocalInputs<C>>().expect(
-        "No local player inputs found. Did you insert systems into the ReadInputs schedule?",
-    );
-    for (handle, input) in local_inputs.0 {
+    let local_inputs = read_local_inputs::<C>(world);
+    for (handle, input) in local_inputs {
         sess.add_local_input(handle, input)
             .expect("All handles in local_handles should be valid");
     }
@@ -89,6 +290,10 @@ pub(crate) fn run_synctest<C: Config>(world: &mut World, mut sess: SyncTestSessi
 
     match requests {
         Ok(requests) => handle_requests(requests, world),
+        Err(e @ GGRSError::MismatchedChecksum { .. }) => {
+            warn!("{e}");
+            report_sync_test_mismatches(world);
+        }
         Err(e) => warn!("{e}"),
     }
 }
@@ -117,13 +322,9 @@ pub(crate) fn run_p2p<C: Config>(world: &mut World, mut sess: P2PSession<C>) {
 
     if running {
         // get local player inputs
-        world.run_schedule(ReadInputs);
-
-        let local_inputs = world.remove_resource::<LocalInputs<C>>().expect(
-            "No local player inputs found. Did you insert systems into the ReadInputs schedule?",
-        );
+        let local_inputs = read_local_inputs::<C>(world);
 
-        for (handle, input) in local_inputs.0 {
+        for (handle, input) in local_inputs {
             sess.add_local_input(handle, input)
                 .expect("All handles in local_inputs should be valid");
         }
@@ -143,6 +344,22 @@ pub(crate) fn run_p2p<C: Config>(world: &mut World, mut sess: P2PSession<C>) {
     }
 }
 
+pub(crate) fn run_replay<C: Config>(world: &mut World, mut sess: ReplaySession<C>) {
+    world.insert_resource(LocalPlayers::default());
+
+    // keep the frame count in sync with the recording, even if it started mid-session
+    world.insert_resource(RollbackFrameCount(sess.next_frame() - 1));
+
+    let requests = sess.advance_frame();
+
+    world.insert_resource(Session::Replay(sess));
+
+    match requests {
+        Some(requests) => handle_requests(requests, world),
+        None => debug!("ReplaySession: Replay finished."),
+    }
+}
+
 pub(crate) fn handle_requests<T: Config>(requests: Vec<GGRSRequest<T>>, world: &mut World) {
     let _span = bevy::utils::tracing::info_span!("ggrs", name = "HandleRequests").entered();
 
@@ -167,6 +384,23 @@ pub(crate) fn handle_requests<T: Config>(requests: Vec<GGRSRequest<T>>, world: &
         panic!("Could not extract GgrsSchedule Schedule!");
     };
 
+    // GGRS counts frames from the start of the session
+    let start_frame = world
+        .get_resource::<SessionStartFrame>()
+        .map(|frame| frame.0)
+        .unwrap_or_default();
+
+    // A SyncTest session only loads frames within its check distance of the frame it started
+    // this update from, so older frames will never be simulated again
+    let initial_frame = world
+        .get_resource::<RollbackFrameCount>()
+        .map(|frame| frame.0)
+        .unwrap_or_default();
+
+    // Only measure the simulation while a GgrsDiagnosticsPlugin is collecting metrics
+    let mut metrics = world.remove_resource::<RollbackMetrics>();
+    let mut rollback_depth = None;
+
     // Run Schedules as Required
     for request in requests {
         let current_frame = world
@@ -180,16 +414,17 @@ pub(crate) fn handle_requests<T: Config>(requests: Vec<GGRSRequest<T>>, world: &
             Some(Session::P2P(s)) => Some(s.max_prediction()),
             Some(Session::SyncTest(s)) => Some(s.max_prediction()),
             Some(Session::Spectator(_)) => Some(0),
+            Some(Session::Replay(_)) => Some(0),
             None => None,
         };
 
         let confirmed_frame = match session {
-            Some(Session::P2P(s)) => Some(s.confirmed_frame()),
+            Some(Session::P2P(s)) => Some(s.confirmed_frame() + start_frame),
             Some(Session::SyncTest(s)) => {
-                let current_frame = current_frame - (s.check_distance() as i32);
-                (current_frame < 0).then_some(current_frame)
+                Some((initial_frame - s.check_distance() as i32).max(start_frame - 1))
             }
             Some(Session::Spectator(_)) => Some(current_frame),
+            Some(Session::Replay(_)) => Some(current_frame),
             None => None,
         };
 
@@ -207,8 +442,13 @@ pub(crate) fn handle_requests<T: Config>(requests: Vec<GGRSRequest<T>>, world: &
                     bevy::utils::tracing::info_span!("schedule", name = "SaveWorld").entered();
                 debug!("saving snapshot for frame {frame}");
 
+                let start = Instant::now();
                 save_world_schedule.run(world);
 
+                if let Some(metrics) = metrics.as_mut() {
+                    metrics.save_time += start.elapsed();
+                }
+
                 // look into resources and find the checksum
                 let checksum = world
                     .get_resource::<Checksum>()
@@ -226,9 +466,15 @@ pub(crate) fn handle_requests<T: Config>(requests: Vec<GGRSRequest<T>>, world: &
                 world
                     .get_resource_mut::<RollbackFrameCount>()
                     .expect("Unable to find GGRS RollbackFrameCount. Did you remove it?")
-                    .0 = frame;
+                    .0 = frame + start_frame;
 
+                let start = Instant::now();
                 load_world_schedule.run(world);
+
+                if let Some(metrics) = metrics.as_mut() {
+                    metrics.load_time += start.elapsed();
+                    metrics.record_rollback(rollback_depth.replace(0));
+                }
             }
             GGRSRequest::AdvanceFrame { inputs } => {
                 let _span =
@@ -241,9 +487,41 @@ pub(crate) fn handle_requests<T: Config>(requests: Vec<GGRSRequest<T>>, world: &
                 let frame = frame_count.0;
 
                 debug!("advancing to frame: {}", frame);
+
+                if let Some(mut recording) = world.get_resource_mut::<InputRecording<T>>() {
+                    recording.record(frame, &inputs);
+                }
+
                 world.insert_resource(PlayerInputs::<T>(inputs));
 
+                let resimulating = world
+                    .get_resource::<HighestSimulatedFrame>()
+                    .is_some_and(|&highest| frame <= i32::from(highest));
+
+                if let Some(mut profiler) = world.get_resource_mut::<GgrsProfiler>() {
+                    profiler.begin_frame(resimulating);
+                }
+
+                let start = Instant::now();
                 advance_world_schedule.run(world);
+                let elapsed = start.elapsed();
+
+                if let Some(metrics) = metrics.as_mut() {
+                    metrics.advance_time += elapsed;
+                    metrics.frames += 1;
+                }
+
+                if let Some(mut profiler) = world.get_resource_mut::<GgrsProfiler>() {
+                    profiler.end_frame(elapsed);
+                }
+
+                if let Some(depth) = rollback_depth.as_mut() {
+                    *depth += 1;
+                }
+
+                if !resimulating {
+                    world.insert_resource(HighestSimulatedFrame(frame));
+                }
 
                 world.remove_resource::<PlayerInputs<T>>();
                 debug!("frame {frame} completed");
@@ -251,6 +529,11 @@ pub(crate) fn handle_requests<T: Config>(requests: Vec<GGRSRequest<T>>, world: &
         }
     }
 
+    if let Some(mut metrics) = metrics {
+        metrics.record_rollback(rollback_depth);
+        world.insert_resource(metrics);
+    }
+
     // Replace Schedules when Done
     let mut schedules = world.resource_mut::<Schedules>();
 
@@ -268,4 +551,6 @@ pub(crate) fn handle_requests<T: Config>(requests: Vec<GGRSRequest<T>>, world: &
     if old.is_some() {
         panic!("GgrsSchedule Schedule was Duplicated!");
     }
+
+    world.run_schedule(RequestsHandled);
 }
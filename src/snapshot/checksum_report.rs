@@ -0,0 +1,472 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, Read, Write},
+};
+
+use bevy::{prelude::*, utils::HashMap};
+
+use ggrs::{Config, GGRSEvent};
+
+use crate::{
+    Checksum, ChecksumPart, ChecksumPlugin, GgrsSessionEvent, Rollback, RollbackFrameCount,
+    RollbackOrdered, SaveWorld, SaveWorldSet, DEFAULT_FPS,
+};
+
+/// The largest number of parts or entities preallocated when reading a report. Larger reports are
+/// still read, but a corrupt or malicious report cannot cause a huge allocation.
+const REPORT_MAX_PREALLOCATED_ITEMS: usize = 1 << 12;
+
+/// The longest part name accepted when reading a report.
+const REPORT_MAX_NAME_LENGTH: usize = 1 << 12;
+
+/// Describes what contributed to a [`ChecksumPart`]. This [`Component`] is maintained by
+/// [`ComponentChecksumHashPlugin`](`crate::ComponentChecksumHashPlugin`) and
+/// [`ResourceChecksumHashPlugin`](`crate::ResourceChecksumHashPlugin`) while a [`ChecksumHistory`]
+/// is present.
+#[derive(Component, Default, Clone, Debug)]
+pub struct ChecksumPartDetails {
+    /// Name of the type this [`ChecksumPart`] was produced for.
+    pub name: &'static str,
+    /// Individual hashes of every [`Rollback`] entity which participated in the [`ChecksumPart`].
+    pub entities: Vec<(Rollback, u64)>,
+}
+
+/// The contribution of a single [`Rollback`] entity to a [`ChecksumReportPart`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChecksumReportEntity {
+    /// The local [`Rollback`] flag of this entity.
+    pub rollback: Rollback,
+    /// The stable id of this entity, as provided by [`RollbackOrdered::id`].
+    /// Unlike `rollback`, this can be compared across peers.
+    pub order: u64,
+    /// The hash for this entity.
+    pub checksum: u64,
+}
+
+/// The contribution of a single type to a [`ChecksumReport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChecksumReportPart {
+    /// Name of the type this part was produced for.
+    pub name: String,
+    /// The [`ChecksumPart`] value.
+    pub checksum: u128,
+    /// Per-entity hashes, sorted by [`order`](`ChecksumReportEntity::order`).
+    /// Empty for [`Resources`](`Resource`).
+    pub entities: Vec<ChecksumReportEntity>,
+}
+
+/// A breakdown of the [`Checksum`] for a single frame, by type and by [`Rollback`] entity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChecksumReport {
+    /// The frame this report was generated for.
+    pub frame: i32,
+    /// The total [`Checksum`] for this frame.
+    pub checksum: u128,
+    /// Every [`ChecksumPart`] which contributed to the [`Checksum`], sorted by name.
+    pub parts: Vec<ChecksumReportPart>,
+}
+
+/// A single difference between two [`ChecksumReports`](`ChecksumReport`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChecksumMismatch {
+    /// The [`ChecksumPart`] for a type differs, or only exists in one report.
+    Part {
+        name: String,
+        local: Option<u128>,
+        remote: Option<u128>,
+    },
+    /// The hash for a particular entity differs, or it only exists in one report.
+    Entity {
+        name: String,
+        order: u64,
+        local: Option<u64>,
+        remote: Option<u64>,
+    },
+}
+
+impl ChecksumReport {
+    /// Compare this (local) report against a report from another peer, listing every type and entity
+    /// which diverged. Entities are matched by their [`RollbackOrdered`] id.
+    pub fn diff(&self, remote: &ChecksumReport) -> Vec<ChecksumMismatch> {
+        let mut mismatches = Vec::new();
+
+        let remote_parts = remote
+            .parts
+            .iter()
+            .map(|part| (part.name.as_str(), part))
+            .collect::<HashMap<_, _>>();
+
+        for local_part in &self.parts {
+            let Some(remote_part) = remote_parts.get(local_part.name.as_str()) else {
+                mismatches.push(ChecksumMismatch::Part {
+                    name: local_part.name.clone(),
+                    local: Some(local_part.checksum),
+                    remote: None,
+                });
+                continue;
+            };
+
+            if local_part.checksum == remote_part.checksum {
+                continue;
+            }
+
+            mismatches.push(ChecksumMismatch::Part {
+                name: local_part.name.clone(),
+                local: Some(local_part.checksum),
+                remote: Some(remote_part.checksum),
+            });
+
+            let mut entities: HashMap<u64, (Option<u64>, Option<u64>)> = HashMap::new();
+
+            for entity in &local_part.entities {
+                entities.entry(entity.order).or_default().0 = Some(entity.checksum);
+            }
+
+            for entity in &remote_part.entities {
+                entities.entry(entity.order).or_default().1 = Some(entity.checksum);
+            }
+
+            let mut entities = entities
+                .into_iter()
+                .filter(|(_, (local, remote))| local != remote)
+                .collect::<Vec<_>>();
+
+            entities.sort_by_key(|&(order, _)| order);
+
+            mismatches.extend(entities.into_iter().map(|(order, (local, remote))| {
+                ChecksumMismatch::Entity {
+                    name: local_part.name.clone(),
+                    order,
+                    local,
+                    remote,
+                }
+            }));
+        }
+
+        for remote_part in &remote.parts {
+            if !self.parts.iter().any(|part| part.name == remote_part.name) {
+                mismatches.push(ChecksumMismatch::Part {
+                    name: remote_part.name.clone(),
+                    local: None,
+                    remote: Some(remote_part.checksum),
+                });
+            }
+        }
+
+        mismatches
+    }
+
+    /// Write this report in a compact binary format, suitable for sending to another peer.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(&self.frame.to_le_bytes())?;
+        writer.write_all(&self.checksum.to_le_bytes())?;
+        writer.write_all(&(self.parts.len() as u32).to_le_bytes())?;
+
+        for part in &self.parts {
+            writer.write_all(&(part.name.len() as u32).to_le_bytes())?;
+            writer.write_all(part.name.as_bytes())?;
+            writer.write_all(&part.checksum.to_le_bytes())?;
+            writer.write_all(&(part.entities.len() as u32).to_le_bytes())?;
+
+            for entity in &part.entities {
+                writer.write_all(&entity.order.to_le_bytes())?;
+                writer.write_all(&entity.checksum.to_le_bytes())?;
+            }
+        }
+
+        writer.flush()
+    }
+
+    /// Read a report written by [`write_to`](`ChecksumReport::write_to`).
+    ///
+    /// As [`Rollback`] flags are local to each peer, the entities of the returned report
+    /// will all have [`Rollback`] flags of [`Entity::PLACEHOLDER`].
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let frame = i32::from_le_bytes(read_array(&mut reader)?);
+        let checksum = u128::from_le_bytes(read_array(&mut reader)?);
+        let num_parts = u32::from_le_bytes(read_array(&mut reader)?);
+
+        let mut parts = Vec::with_capacity((num_parts as usize).min(REPORT_MAX_PREALLOCATED_ITEMS));
+
+        for _ in 0..num_parts {
+            let name_len = u32::from_le_bytes(read_array(&mut reader)?) as usize;
+            if name_len > REPORT_MAX_NAME_LENGTH {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "checksum report part name is too long",
+                ));
+            }
+
+            let mut name = vec![0; name_len];
+            reader.read_exact(&mut name)?;
+            let name = String::from_utf8(name)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+            let checksum = u128::from_le_bytes(read_array(&mut reader)?);
+            let num_entities = u32::from_le_bytes(read_array(&mut reader)?);
+
+            let mut entities =
+                Vec::with_capacity((num_entities as usize).min(REPORT_MAX_PREALLOCATED_ITEMS));
+
+            for _ in 0..num_entities {
+                let order = u64::from_le_bytes(read_array(&mut reader)?);
+                let checksum = u64::from_le_bytes(read_array(&mut reader)?);
+
+                entities.push(ChecksumReportEntity {
+                    rollback: Rollback::new(Entity::PLACEHOLDER),
+                    order,
+                    checksum,
+                });
+            }
+
+            parts.push(ChecksumReportPart {
+                name,
+                checksum,
+                entities,
+            });
+        }
+
+        Ok(Self {
+            frame,
+            checksum,
+            parts,
+        })
+    }
+}
+
+impl fmt::Display for ChecksumReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Frame {} has checksum {:X}", self.frame, self.checksum)?;
+
+        for part in &self.parts {
+            writeln!(f, "  {}: {:X}", part.name, part.checksum)?;
+
+            for entity in &part.entities {
+                writeln!(
+                    f,
+                    "    #{} {:?}: {:X}",
+                    entity.order, entity.rollback, entity.checksum
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChecksumMismatch::Part {
+                name,
+                local,
+                remote,
+            } => write!(f, "{name}: local {local:X?}, remote {remote:X?}"),
+            ChecksumMismatch::Entity {
+                name,
+                order,
+                local,
+                remote,
+            } => write!(
+                f,
+                "{name} on #{order}: local {local:X?}, remote {remote:X?}"
+            ),
+        }
+    }
+}
+
+/// A [`Resource`] which keeps [`ChecksumReports`](`ChecksumReport`) for recent frames,
+/// newest first. Frames which are resimulated replace their previous report.
+#[derive(Resource)]
+pub struct ChecksumHistory {
+    reports: VecDeque<ChecksumReport>,
+    depth: usize,
+}
+
+impl Default for ChecksumHistory {
+    fn default() -> Self {
+        Self {
+            reports: VecDeque::with_capacity(DEFAULT_FPS),
+            depth: DEFAULT_FPS,
+        }
+    }
+}
+
+impl ChecksumHistory {
+    /// Set the maximum number of reports to keep.
+    pub fn set_depth(&mut self, depth: usize) -> &mut Self {
+        self.depth = depth;
+        self.reports.truncate(depth);
+        self
+    }
+
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Add a report, discarding any reports for this or later frames.
+    pub fn push(&mut self, report: ChecksumReport) -> &mut Self {
+        while let Some(current) = self.reports.front() {
+            if current.frame >= report.frame {
+                self.reports.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        self.reports.push_front(report);
+        self.reports.truncate(self.depth);
+
+        self
+    }
+
+    /// Get the report for a particular frame, if it is still available.
+    pub fn get(&self, frame: i32) -> Option<&ChecksumReport> {
+        self.reports.iter().find(|report| report.frame == frame)
+    }
+
+    /// Get the most recent report.
+    pub fn latest(&self) -> Option<&ChecksumReport> {
+        self.reports.front()
+    }
+
+    /// Iterate over all stored reports, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &ChecksumReport> + '_ {
+        self.reports.iter()
+    }
+}
+
+/// A [`Plugin`] which records a [`ChecksumReport`] into the [`ChecksumHistory`] every time a
+/// [`Checksum`] is produced. When a desync is detected, the report for the offending frame can be
+/// dumped, or exchanged with the other peer and compared with [`ChecksumReport::diff`], to find
+/// exactly which types and [`Rollback`] entities diverged.
+///
+/// While this plugin is added, the [`GgrsPlugin`](`crate::GgrsPlugin`) logs the local report for
+/// every [`GGRSEvent::DesyncDetected`] it receives, see [`ChecksumReportPlugin::report_desyncs`].
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, ChecksumHistory, ChecksumReportPlugin};
+/// #
+/// # type MyInputType = u8;
+/// #
+/// # fn start(session: Session<GgrsConfig<MyInputType>>) {
+/// # let mut app = App::new();
+/// app.add_plugins(ChecksumReportPlugin);
+///
+/// fn send_report_on_desync(
+///     history: Res<ChecksumHistory>,
+///     mut events: EventReader<GgrsSessionEvent<GgrsConfig<MyInputType>>>,
+/// ) {
+///     for event in events.iter() {
+///         if let GgrsEvent::DesyncDetected { frame, .. } = **event {
+///             if let Some(report) = history.get(frame) {
+///                 let mut bytes = Vec::new();
+///                 report.write_to(&mut bytes).unwrap();
+///                 // Send `bytes` to the other peer, to compare with `ChecksumReport::diff`
+///             }
+///         }
+///     }
+/// }
+/// # }
+/// ```
+pub struct ChecksumReportPlugin;
+
+impl ChecksumReportPlugin {
+    /// A [`System`] responsible for recording a [`ChecksumReport`] for the current frame.
+    pub fn update(
+        mut history: ResMut<ChecksumHistory>,
+        frame: Res<RollbackFrameCount>,
+        checksum: Res<Checksum>,
+        rollback_ordered: Res<RollbackOrdered>,
+        parts: Query<(&ChecksumPart, Option<&ChecksumPartDetails>)>,
+    ) {
+        let mut parts = parts
+            .iter()
+            .map(|(&ChecksumPart(checksum), details)| {
+                let mut entities = details
+                    .map(|details| {
+                        details
+                            .entities
+                            .iter()
+                            .map(|&(rollback, checksum)| ChecksumReportEntity {
+                                rollback,
+                                order: rollback_ordered.id(rollback),
+                                checksum,
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+
+                entities.sort_by_key(|entity| entity.order);
+
+                ChecksumReportPart {
+                    name: details
+                        .map(|details| details.name)
+                        .unwrap_or("<unknown>")
+                        .to_owned(),
+                    checksum,
+                    entities,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        parts.sort_by(|a, b| a.name.cmp(&b.name).then(a.checksum.cmp(&b.checksum)));
+
+        history.push(ChecksumReport {
+            frame: frame.0,
+            checksum: checksum.0,
+            parts,
+        });
+    }
+
+    /// A [`System`] which logs the local [`ChecksumReport`] for every
+    /// [`GGRSEvent::DesyncDetected`] received. Added by the [`GgrsPlugin`](`crate::GgrsPlugin`)
+    /// while a [`ChecksumHistory`] is present.
+    pub fn report_desyncs<C: Config>(
+        history: Res<ChecksumHistory>,
+        mut events: EventReader<GgrsSessionEvent<C>>,
+    ) {
+        for event in events.iter() {
+            let GGRSEvent::DesyncDetected {
+                frame,
+                local_checksum,
+                remote_checksum,
+                ..
+            } = **event
+            else {
+                continue;
+            };
+
+            error!(
+                "Desync detected on frame {frame}: local {local_checksum:X}, remote {remote_checksum:X}"
+            );
+
+            match history.get(frame) {
+                Some(report) => error!("{report}"),
+                None => warn!("No checksum report is available for frame {frame}"),
+            }
+        }
+    }
+}
+
+impl Plugin for ChecksumReportPlugin {
+    fn build(&self, app: &mut App) {
+        // Parts spawned during this save are only counted from the next frame, both here and in
+        // the Checksum, so commands must not be applied early
+        app.init_resource::<ChecksumHistory>().add_systems(
+            SaveWorld,
+            Self::update
+                .after(ChecksumPlugin::update)
+                .before(SaveWorldSet::Snapshot),
+        );
+    }
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
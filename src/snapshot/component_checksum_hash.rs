@@ -5,7 +5,10 @@ use std::{
 
 use bevy::prelude::*;
 
-use crate::{ChecksumFlag, ChecksumPart, Rollback, RollbackOrdered, SaveWorld, SaveWorldSet};
+use crate::{
+    ChecksumFlag, ChecksumHistory, ChecksumPart, ChecksumPartDetails, Rollback, RollbackOrdered,
+    SaveWorld, SaveWorldSet,
+};
 
 /// A [`Plugin`] which will track the [`Component`] `C` on [`Rollback Entities`](`Rollback`) and ensure a
 /// [`ChecksumPart`] is available and updated. This can be used to generate a [`Checksum`](`crate::Checksum`).
@@ -56,26 +59,40 @@ where
     C: Component + Hash,
 {
     /// A [`System`] responsible for managing a [`ChecksumPart`] for the [`Component`] type `C`.
+    ///
+    /// While a [`ChecksumHistory`] is present, the hash of each entity is also recorded in a
+    /// [`ChecksumPartDetails`] alongside the [`ChecksumPart`].
     #[allow(clippy::type_complexity)]
     pub fn update(
         mut commands: Commands,
         rollback_ordered: Res<RollbackOrdered>,
+        history: Option<Res<ChecksumHistory>>,
         components: Query<(&Rollback, &C), (With<Rollback>, Without<ChecksumFlag<C>>)>,
-        mut checksum: Query<&mut ChecksumPart, (Without<Rollback>, With<ChecksumFlag<C>>)>,
+        mut checksum: Query<
+            (Entity, &mut ChecksumPart, Option<&mut ChecksumPartDetails>),
+            (Without<Rollback>, With<ChecksumFlag<C>>),
+        >,
     ) {
         let mut hasher = bevy::utils::FixedState.build_hasher();
 
         let mut result = 0;
+        let mut entities = Vec::new();
 
         for (&rollback, component) in components.iter() {
             let mut hasher = hasher.clone();
 
-            // Hashing the rollback index ensures this hash is unique and stable
-            rollback_ordered.order(rollback).hash(&mut hasher);
+            // Hashing the rollback id ensures this hash is unique and stable
+            rollback_ordered.id(rollback).hash(&mut hasher);
             component.hash(&mut hasher);
 
+            let hash = hasher.finish();
+
+            if history.is_some() {
+                entities.push((rollback, hash));
+            }
+
             // XOR chosen over addition or multiplication as it is closed on u64 and commutative
-            result ^= hasher.finish();
+            result ^= hash;
         }
 
         // Hash the XOR'ed result to break commutativity with other types
@@ -89,10 +106,29 @@ where
             result.0
         );
 
-        if let Ok(mut checksum) = checksum.get_single_mut() {
-            *checksum = result;
-        } else {
-            commands.spawn((result, ChecksumFlag::<C>::default()));
+        let details = history.is_some().then(|| ChecksumPartDetails {
+            name: std::any::type_name::<C>(),
+            entities,
+        });
+
+        match (checksum.get_single_mut(), details) {
+            (Ok((_, mut checksum, Some(mut current))), Some(details)) => {
+                *checksum = result;
+                *current = details;
+            }
+            (Ok((entity, mut checksum, None)), Some(details)) => {
+                *checksum = result;
+                commands.entity(entity).insert(details);
+            }
+            (Ok((_, mut checksum, _)), None) => {
+                *checksum = result;
+            }
+            (Err(_), Some(details)) => {
+                commands.spawn((result, ChecksumFlag::<C>::default(), details));
+            }
+            (Err(_), None) => {
+                commands.spawn((result, ChecksumFlag::<C>::default()));
+            }
         }
     }
 }
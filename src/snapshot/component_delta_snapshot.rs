@@ -0,0 +1,465 @@
+use crate::{
+    snapshot::rolled_back::{insert_rolled_back, mark_rolled_back},
+    ConfirmedFrameCount, LoadWorld, LoadWorldSet, Rollback, RollbackFrameCount, RolledBack,
+    SaveWorld, SaveWorldSet, SnapshotMemory, SnapshotMemoryRegistry, SnapshotReflectRegistry,
+    Strategy, TrackRolledBack, DEFAULT_FPS,
+};
+use bevy::{
+    ecs::system::SystemChangeTick,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::{collections::VecDeque, marker::PhantomData};
+
+/// The changes made to a [`Component`] `For` between two snapshots, stored as `As`.
+pub struct GgrsComponentDelta<For, As = For> {
+    changed: HashMap<Rollback, As>,
+    removed: HashSet<Rollback>,
+    _phantom: PhantomData<For>,
+}
+
+impl<For, As> Default for GgrsComponentDelta<For, As> {
+    fn default() -> Self {
+        Self {
+            changed: default(),
+            removed: default(),
+            _phantom: default(),
+        }
+    }
+}
+
+impl<For, As> GgrsComponentDelta<For, As> {
+    /// Record a changed (or newly added) component.
+    pub fn change(&mut self, rollback: Rollback, snapshot: As) -> &mut Self {
+        self.removed.remove(&rollback);
+        self.changed.insert(rollback, snapshot);
+        self
+    }
+
+    /// Record a removed component.
+    pub fn remove(&mut self, rollback: Rollback) -> &mut Self {
+        self.changed.remove(&rollback);
+        self.removed.insert(rollback);
+        self
+    }
+
+    /// The number of changed and removed components in this delta.
+    pub fn len(&self) -> usize {
+        self.changed.len() + self.removed.len()
+    }
+
+    /// Returns `true` if nothing changed, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    fn apply_to<'a>(&'a self, state: &mut HashMap<Rollback, &'a As>) {
+        for rollback in &self.removed {
+            state.remove(rollback);
+        }
+
+        for (&rollback, snapshot) in &self.changed {
+            state.insert(rollback, snapshot);
+        }
+    }
+
+    /// The snapshot recorded for the provided [`Rollback`] by this delta. The outer [`Option`]
+    /// is [`None`] if this delta did not touch it.
+    fn lookup(&self, rollback: &Rollback) -> Option<Option<&As>> {
+        if let Some(snapshot) = self.changed.get(rollback) {
+            return Some(Some(snapshot));
+        }
+
+        self.removed.contains(rollback).then_some(None)
+    }
+}
+
+/// Collection of delta snapshots for a [`Component`] `For`, stored as `As`. Rather than storing
+/// every component for every frame, only the components which changed since the previous
+/// snapshot are stored. The full state of older frames is folded into a single base snapshot.
+#[derive(Resource)]
+pub struct GgrsComponentDeltaSnapshots<For, As = For> {
+    /// The full state as of the oldest frame still available.
+    base: HashMap<Rollback, As>,
+    /// Queue of deltas since `base`, newest at the front, oldest at the back.
+    deltas: VecDeque<(i32, GgrsComponentDelta<For, As>)>,
+    /// The [`Rollback`] entities which have a snapshot in the most recent frame.
+    latest: HashSet<Rollback>,
+    /// Maximum amount of deltas to store at any one time
+    depth: usize,
+}
+
+impl<For, As> Default for GgrsComponentDeltaSnapshots<For, As> {
+    fn default() -> Self {
+        Self {
+            base: default(),
+            deltas: VecDeque::with_capacity(DEFAULT_FPS),
+            latest: default(),
+            depth: DEFAULT_FPS,
+        }
+    }
+}
+
+impl<For, As> GgrsComponentDeltaSnapshots<For, As> {
+    pub fn set_depth(&mut self, depth: usize) -> &mut Self {
+        self.depth = depth.max(1);
+
+        while self.deltas.len() > self.depth {
+            self.fold_oldest();
+        }
+
+        self
+    }
+
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns `true` if no snapshot has been stored yet, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.deltas.is_empty()
+    }
+
+    /// Store the changes made since the previous snapshot as the snapshot for the provided frame,
+    /// discarding any snapshots for this frame or later.
+    pub fn push(&mut self, frame: i32, delta: GgrsComponentDelta<For, As>) -> &mut Self {
+        while let Some(&(current, _)) = self.deltas.front() {
+            if current >= frame {
+                self.pop_latest();
+            } else {
+                break;
+            }
+        }
+
+        for rollback in &delta.removed {
+            self.latest.remove(rollback);
+        }
+
+        self.latest.extend(delta.changed.keys().copied());
+        self.deltas.push_front((frame, delta));
+
+        while self.deltas.len() > self.depth {
+            self.fold_oldest();
+        }
+
+        self
+    }
+
+    /// Fold all snapshots older than the confirmed frame into the base snapshot.
+    pub fn confirm(&mut self, confirmed_frame: i32) -> &mut Self {
+        // Always keep the confirmed frame itself available
+        while self.deltas.len() > 1 {
+            match self.deltas.get(self.deltas.len() - 2) {
+                Some(&(next, _)) if next <= confirmed_frame => self.fold_oldest(),
+                _ => break,
+            }
+        }
+
+        self
+    }
+
+    /// Discard all snapshots after the provided frame.
+    ///
+    /// # Panics
+    /// If no snapshot exists for the provided frame.
+    pub fn rollback(&mut self, frame: i32) -> &mut Self {
+        loop {
+            let Some(&(current, _)) = self.deltas.front() else {
+                panic!("Could not rollback to {frame}: no snapshot at that moment could be found.");
+            };
+
+            if current == frame {
+                break;
+            }
+
+            if current < frame {
+                panic!("Could not rollback to {frame}: no snapshot at that moment could be found.");
+            }
+
+            self.pop_latest();
+        }
+
+        self
+    }
+
+    /// The snapshot for the provided [`Rollback`] in the most recent frame.
+    pub fn get(&self, rollback: &Rollback) -> Option<&As> {
+        self.lookup(0, rollback)
+    }
+
+    /// Iterate over every snapshot in the most recent frame.
+    pub fn iter(&self) -> impl Iterator<Item = (Rollback, &As)> + '_ {
+        self.latest
+            .iter()
+            .filter_map(|rollback| Some((*rollback, self.get(rollback)?)))
+    }
+
+    /// Reconstruct the full state for the provided frame, if a snapshot for it is still stored.
+    /// Prefer [`get`](`Self::get`) or [`iter`](`Self::iter`) for the most recent frame, which do
+    /// not need to reconstruct it.
+    pub fn peek(&self, frame: i32) -> Option<HashMap<Rollback, &As>> {
+        let index = self
+            .deltas
+            .iter()
+            .position(|&(saved_frame, _)| saved_frame == frame)?;
+
+        Some(self.reconstruct(index))
+    }
+
+    /// The [`Rollback`] entities which had this component in the most recent snapshot.
+    pub fn latest_rollbacks(&self) -> &HashSet<Rollback> {
+        &self.latest
+    }
+
+    /// Find the snapshot for the provided [`Rollback`] as of the delta at the provided index.
+    fn lookup(&self, index: usize, rollback: &Rollback) -> Option<&As> {
+        self.deltas
+            .iter()
+            .skip(index)
+            .find_map(|(_, delta)| delta.lookup(rollback))
+            .unwrap_or_else(|| self.base.get(rollback))
+    }
+
+    /// Discard the most recent delta, restoring the [`Rollback`] entities it touched in
+    /// `latest` to their state in the previous snapshot.
+    fn pop_latest(&mut self) {
+        let Some((_, delta)) = self.deltas.pop_front() else {
+            return;
+        };
+
+        for rollback in delta.changed.keys().chain(&delta.removed) {
+            if self.lookup(0, rollback).is_some() {
+                self.latest.insert(*rollback);
+            } else {
+                self.latest.remove(rollback);
+            }
+        }
+    }
+
+    /// Apply all deltas from the oldest up to and including the provided index onto the base.
+    fn reconstruct(&self, index: usize) -> HashMap<Rollback, &As> {
+        let mut state = self
+            .base
+            .iter()
+            .map(|(&rollback, snapshot)| (rollback, snapshot))
+            .collect::<HashMap<_, _>>();
+
+        for (_, delta) in self.deltas.iter().skip(index).rev() {
+            delta.apply_to(&mut state);
+        }
+
+        state
+    }
+
+    fn fold_oldest(&mut self) {
+        let Some((_, delta)) = self.deltas.pop_back() else {
+            return;
+        };
+
+        for rollback in delta.removed {
+            self.base.remove(&rollback);
+        }
+
+        self.base.extend(delta.changed);
+    }
+
+    pub fn discard_old_snapshots(
+        mut snapshots: ResMut<Self>,
+        confirmed_frame: Option<Res<ConfirmedFrameCount>>,
+    ) where
+        For: Send + Sync + 'static,
+        As: Send + Sync + 'static,
+    {
+        let Some(confirmed_frame) = confirmed_frame else {
+            return;
+        };
+
+        snapshots.confirm(confirmed_frame.0);
+    }
+}
+
+impl<For, As> SnapshotMemory for GgrsComponentDeltaSnapshots<For, As> {
+    fn memory_usage(&self) -> usize {
+        let entry = std::mem::size_of::<(Rollback, As)>();
+        let removed = std::mem::size_of::<Rollback>();
+
+        self.base.len() * entry
+            + self
+                .deltas
+                .iter()
+                .map(|(_, delta)| delta.changed.len() * entry + delta.removed.len() * removed)
+                .sum::<usize>()
+    }
+}
+
+/// A [`Plugin`] which manages delta snapshots for a [`Component`] using a provided [`Strategy`].
+///
+/// Unlike [`ComponentSnapshotPlugin`](`crate::ComponentSnapshotPlugin`), only components which
+/// were changed (according to Bevy's change detection) since the previous snapshot are stored.
+/// This greatly reduces allocations for types which rarely change across many entities, at the
+/// cost of reconstructing the full state when rolling back. As loading a snapshot marks every
+/// component as changed, the first snapshot after a rollback will contain all components.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, DeltaComponentSnapshotPlugin, CloneStrategy};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// #[derive(Component, Clone)]
+/// struct Inventory(Vec<u32>);
+///
+/// // Inventories rarely change, so only store the ones which did
+/// app.add_plugins(DeltaComponentSnapshotPlugin::<CloneStrategy<Inventory>>::default());
+/// # }
+/// ```
+pub struct DeltaComponentSnapshotPlugin<S>
+where
+    S: Strategy,
+    S::Target: Component,
+    S::Stored: Send + Sync + 'static,
+{
+    _phantom: PhantomData<S>,
+}
+
+impl<S> Default for DeltaComponentSnapshotPlugin<S>
+where
+    S: Strategy,
+    S::Target: Component,
+    S::Stored: Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: default(),
+        }
+    }
+}
+
+impl<S> DeltaComponentSnapshotPlugin<S>
+where
+    S: Strategy,
+    S::Target: Component,
+    S::Stored: Send + Sync + 'static,
+{
+    pub fn save(
+        mut snapshots: ResMut<GgrsComponentDeltaSnapshots<S::Target, S::Stored>>,
+        frame: Res<RollbackFrameCount>,
+        query: Query<(&Rollback, Ref<S::Target>)>,
+    ) {
+        let full = snapshots.is_empty();
+        let latest = snapshots.latest_rollbacks();
+        let mut delta = GgrsComponentDelta::default();
+        let mut retained = 0;
+
+        for (&rollback, component) in query.iter() {
+            let existed = latest.contains(&rollback);
+
+            if existed {
+                retained += 1;
+            }
+
+            if full || !existed || component.is_changed() {
+                delta.change(rollback, S::store(&component));
+            }
+        }
+
+        // Components are rarely removed, so only look for which were when required
+        if retained < latest.len() {
+            let current = query
+                .iter()
+                .map(|(&rollback, _)| rollback)
+                .collect::<HashSet<_>>();
+
+            for &rollback in latest.difference(&current) {
+                delta.remove(rollback);
+            }
+        }
+
+        trace!(
+            "Snapshot {} changed {} component(s)",
+            delta.len(),
+            bevy::utils::get_short_name(std::any::type_name::<S::Target>())
+        );
+
+        snapshots.push(frame.0, delta);
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn load(
+        mut commands: Commands,
+        mut snapshots: ResMut<GgrsComponentDeltaSnapshots<S::Target, S::Stored>>,
+        frame: Res<RollbackFrameCount>,
+        system_tick: SystemChangeTick,
+        track_marker: Option<Res<TrackRolledBack<S::Target>>>,
+        mut query: Query<(
+            Entity,
+            &Rollback,
+            Option<&mut S::Target>,
+            Option<&mut RolledBack<S::Target>>,
+        )>,
+    ) {
+        let track_marker = track_marker.is_some();
+
+        let snapshots = snapshots.rollback(frame.0);
+
+        for (entity, rollback, component, marker) in query.iter_mut() {
+            match (component, snapshots.get(rollback)) {
+                (Some(mut component), Some(snapshot)) => {
+                    S::update(component.as_mut(), snapshot);
+                    mark_rolled_back(
+                        &mut commands,
+                        entity,
+                        marker,
+                        system_tick.this_run(),
+                        track_marker,
+                    );
+                }
+                (Some(_), None) => {
+                    commands
+                        .entity(entity)
+                        .remove::<(S::Target, RolledBack<S::Target>)>();
+                }
+                (None, Some(snapshot)) => {
+                    insert_rolled_back(&mut commands, entity, S::load(snapshot), track_marker);
+                }
+                (None, None) => {}
+            }
+        }
+
+        trace!(
+            "Rolled back {} {} component(s)",
+            snapshots.latest_rollbacks().len(),
+            bevy::utils::get_short_name(std::any::type_name::<S::Target>())
+        );
+    }
+}
+
+impl<S> Plugin for DeltaComponentSnapshotPlugin<S>
+where
+    S: Send + Sync + 'static + Strategy,
+    S::Target: Component,
+    S::Stored: Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.world
+            .get_resource_or_insert_with(SnapshotReflectRegistry::default)
+            .register_delta_component::<S>();
+
+        app.world
+            .get_resource_or_insert_with(SnapshotMemoryRegistry::default)
+            .register::<S::Target, GgrsComponentDeltaSnapshots<S::Target, S::Stored>>();
+
+        app.init_resource::<GgrsComponentDeltaSnapshots<S::Target, S::Stored>>()
+            .add_systems(
+                SaveWorld,
+                (
+                    GgrsComponentDeltaSnapshots::<S::Target, S::Stored>::discard_old_snapshots,
+                    Self::save,
+                )
+                    .chain()
+                    .in_set(SaveWorldSet::Snapshot),
+            )
+            .add_systems(LoadWorld, Self::load.in_set(LoadWorldSet::Data));
+    }
+}
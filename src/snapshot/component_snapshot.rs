@@ -1,8 +1,10 @@
 use crate::{
+    snapshot::rolled_back::{insert_rolled_back, mark_rolled_back},
     GgrsComponentSnapshot, GgrsComponentSnapshots, LoadWorld, LoadWorldSet, Rollback,
-    RollbackFrameCount, SaveWorld, SaveWorldSet, Strategy,
+    RollbackFrameCount, RolledBack, SaveWorld, SaveWorldSet, SnapshotMemoryRegistry,
+    SnapshotReflectRegistry, Strategy, TrackRolledBack,
 };
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemChangeTick, prelude::*};
 use std::marker::PhantomData;
 
 /// A [`Plugin`] which manages snapshots for a [`Component`] using a provided [`Strategy`].
@@ -72,24 +74,45 @@ where
         snapshots.push(frame.0, snapshot);
     }
 
+    #[allow(clippy::type_complexity)]
     pub fn load(
         mut commands: Commands,
         mut snapshots: ResMut<GgrsComponentSnapshots<S::Target, S::Stored>>,
         frame: Res<RollbackFrameCount>,
-        mut query: Query<(Entity, &Rollback, Option<&mut S::Target>)>,
+        system_tick: SystemChangeTick,
+        track_marker: Option<Res<TrackRolledBack<S::Target>>>,
+        mut query: Query<(
+            Entity,
+            &Rollback,
+            Option<&mut S::Target>,
+            Option<&mut RolledBack<S::Target>>,
+        )>,
     ) {
+        let track_marker = track_marker.is_some();
+
         let snapshot = snapshots.rollback(frame.0).get();
 
-        for (entity, rollback, component) in query.iter_mut() {
+        for (entity, rollback, component, marker) in query.iter_mut() {
             let snapshot = snapshot.get(rollback);
 
             match (component, snapshot) {
-                (Some(mut component), Some(snapshot)) => S::update(component.as_mut(), snapshot),
+                (Some(mut component), Some(snapshot)) => {
+                    S::update(component.as_mut(), snapshot);
+                    mark_rolled_back(
+                        &mut commands,
+                        entity,
+                        marker,
+                        system_tick.this_run(),
+                        track_marker,
+                    );
+                }
                 (Some(_), None) => {
-                    commands.entity(entity).remove::<S::Target>();
+                    commands
+                        .entity(entity)
+                        .remove::<(S::Target, RolledBack<S::Target>)>();
                 }
                 (None, Some(snapshot)) => {
-                    commands.entity(entity).insert(S::load(snapshot));
+                    insert_rolled_back(&mut commands, entity, S::load(snapshot), track_marker);
                 }
                 (None, None) => {}
             }
@@ -110,6 +133,14 @@ where
     S::Stored: Send + Sync + 'static,
 {
     fn build(&self, app: &mut App) {
+        app.world
+            .get_resource_or_insert_with(SnapshotReflectRegistry::default)
+            .register_component::<S>();
+
+        app.world
+            .get_resource_or_insert_with(SnapshotMemoryRegistry::default)
+            .register::<S::Target, GgrsComponentSnapshots<S::Target, S::Stored>>();
+
         app.init_resource::<GgrsComponentSnapshots<S::Target, S::Stored>>()
             .add_systems(
                 SaveWorld,
@@ -0,0 +1,245 @@
+use crate::{
+    snapshot::rolled_back::{insert_rolled_back, mark_rolled_back},
+    GgrsComponentSnapshot, GgrsComponentSnapshots, LoadWorld, LoadWorldSet, Rollback,
+    RollbackFrameCount, RolledBack, SaveWorld, SaveWorldSet, SnapshotMemoryRegistry,
+    SnapshotReflectRegistry, Strategy, TrackRolledBack,
+};
+use bevy::{
+    ecs::{component::Tick, system::SystemChangeTick},
+    prelude::*,
+};
+use std::{marker::PhantomData, sync::Arc};
+
+/// Typical [`Resource`] used to store change-tracked snapshots for a [`Component`] `C` as the
+/// type `As`. Unchanged components share their storage with the previous snapshot.
+pub type GgrsTrackedComponentSnapshots<C, As = C> = GgrsComponentSnapshots<C, Arc<As>>;
+
+/// The [`Tick`] at which change-tracked snapshots of a [`Component`] `C` were last saved or loaded.
+///
+/// Components which have not changed since this tick still hold the value of the newest snapshot.
+#[derive(Resource)]
+pub struct GgrsTrackedSnapshotTick<C> {
+    tick: Tick,
+    _phantom: PhantomData<C>,
+}
+
+impl<C> Default for GgrsTrackedSnapshotTick<C> {
+    fn default() -> Self {
+        Self {
+            tick: Tick::new(0),
+            _phantom: default(),
+        }
+    }
+}
+
+impl<C> GgrsTrackedSnapshotTick<C> {
+    pub fn tick(&self) -> Tick {
+        self.tick
+    }
+}
+
+/// A [`Plugin`] which manages change-tracked snapshots for a [`Component`] using a provided
+/// [`Strategy`].
+///
+/// Components which were not changed since the previous snapshot are not stored again, instead
+/// sharing the previous snapshot through an [`Arc`]. When rolling back, only components whose
+/// stored value differs from the current value are written back, so [`Changed`] and
+/// [`Ref::is_changed`] remain meaningful after a rollback. Components which did not change since
+/// the newest snapshot are compared with it by reference rather than stored again, which requires
+/// the [`Stored`](`Strategy::Stored`) type to implement [`PartialEq`].
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, TrackedComponentSnapshotPlugin, CopyStrategy};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// #[derive(Component, Clone, Copy, PartialEq)]
+/// struct Team(u8);
+///
+/// // Teams rarely change, so avoid storing and restoring them every frame
+/// app.add_plugins(TrackedComponentSnapshotPlugin::<CopyStrategy<Team>>::default());
+/// # }
+/// ```
+pub struct TrackedComponentSnapshotPlugin<S>
+where
+    S: Strategy,
+    S::Target: Component,
+    S::Stored: PartialEq + Send + Sync + 'static,
+{
+    _phantom: PhantomData<S>,
+}
+
+impl<S> Default for TrackedComponentSnapshotPlugin<S>
+where
+    S: Strategy,
+    S::Target: Component,
+    S::Stored: PartialEq + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: default(),
+        }
+    }
+}
+
+impl<S> TrackedComponentSnapshotPlugin<S>
+where
+    S: Strategy,
+    S::Target: Component,
+    S::Stored: PartialEq + Send + Sync + 'static,
+{
+    pub fn save(
+        mut snapshots: ResMut<GgrsTrackedComponentSnapshots<S::Target, S::Stored>>,
+        mut snapshot_tick: ResMut<GgrsTrackedSnapshotTick<S::Target>>,
+        frame: Res<RollbackFrameCount>,
+        system_tick: SystemChangeTick,
+        query: Query<(&Rollback, Ref<S::Target>)>,
+    ) {
+        let mut stored = 0;
+
+        // Components which have not changed since the last save still match the previous frame,
+        // as loading a snapshot writes back any component which differs from it
+        let previous = snapshots.peek(frame.0 - 1);
+
+        let components = query
+            .iter()
+            .map(|(&rollback, component)| {
+                let shared = previous
+                    .filter(|_| !component.is_changed())
+                    .and_then(|previous| previous.get(&rollback))
+                    .cloned();
+
+                let snapshot = shared.unwrap_or_else(|| {
+                    stored += 1;
+                    Arc::new(S::store(&component))
+                });
+
+                (rollback, snapshot)
+            })
+            .collect::<Vec<_>>();
+
+        let snapshot = GgrsComponentSnapshot::new(components);
+
+        trace!(
+            "Snapshot {} {} component(s), {} changed",
+            snapshot.iter().count(),
+            bevy::utils::get_short_name(std::any::type_name::<S::Target>()),
+            stored
+        );
+
+        snapshots.push(frame.0, snapshot);
+        snapshot_tick.tick = system_tick.this_run();
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn load(
+        mut commands: Commands,
+        mut snapshots: ResMut<GgrsTrackedComponentSnapshots<S::Target, S::Stored>>,
+        mut snapshot_tick: ResMut<GgrsTrackedSnapshotTick<S::Target>>,
+        frame: Res<RollbackFrameCount>,
+        system_tick: SystemChangeTick,
+        track_marker: Option<Res<TrackRolledBack<S::Target>>>,
+        mut query: Query<(
+            Entity,
+            &Rollback,
+            Option<&mut S::Target>,
+            Option<&mut RolledBack<S::Target>>,
+        )>,
+    ) {
+        let track_marker = track_marker.is_some();
+
+        // The newest snapshot is only discarded once every component has been compared with it
+        let Some(snapshot) = snapshots.peek(frame.0) else {
+            panic!(
+                "Could not rollback to {}: no snapshot at that moment could be found.",
+                frame.0
+            );
+        };
+        let latest = snapshots.get();
+        let mut restored = 0;
+        let total = snapshot.iter().count();
+
+        for (entity, rollback, component, marker) in query.iter_mut() {
+            let snapshot = snapshot.get(rollback);
+
+            match (component, snapshot) {
+                (Some(mut component), Some(snapshot)) => {
+                    // Components unchanged since the newest snapshot still hold its value, so they
+                    // can be compared by reference. Anything else is written back unconditionally.
+                    let unchanged = !component
+                        .last_changed()
+                        .is_newer_than(snapshot_tick.tick, system_tick.this_run())
+                        && latest.get(rollback).is_some_and(|latest| {
+                            Arc::ptr_eq(latest, snapshot) || **latest == **snapshot
+                        });
+
+                    // Only write back (and trigger change detection) if the value differs
+                    if !unchanged {
+                        S::update(component.as_mut(), snapshot);
+                        mark_rolled_back(
+                            &mut commands,
+                            entity,
+                            marker,
+                            system_tick.this_run(),
+                            track_marker,
+                        );
+                        restored += 1;
+                    }
+                }
+                (Some(_), None) => {
+                    commands
+                        .entity(entity)
+                        .remove::<(S::Target, RolledBack<S::Target>)>();
+                }
+                (None, Some(snapshot)) => {
+                    insert_rolled_back(&mut commands, entity, S::load(snapshot), track_marker);
+                    restored += 1;
+                }
+                (None, None) => {}
+            }
+        }
+
+        // Every component now holds the value of the snapshot rolled back to
+        snapshots.rollback(frame.0);
+        snapshot_tick.tick = system_tick.this_run();
+
+        trace!(
+            "Rolled back {} of {} {} component(s)",
+            restored,
+            total,
+            bevy::utils::get_short_name(std::any::type_name::<S::Target>())
+        );
+    }
+}
+
+impl<S> Plugin for TrackedComponentSnapshotPlugin<S>
+where
+    S: Send + Sync + 'static + Strategy,
+    S::Target: Component,
+    S::Stored: PartialEq + Send + Sync + 'static,
+{
+    fn build(&self, app: &mut App) {
+        app.world
+            .get_resource_or_insert_with(SnapshotReflectRegistry::default)
+            .register_tracked_component::<S>();
+
+        app.world
+            .get_resource_or_insert_with(SnapshotMemoryRegistry::default)
+            .register::<S::Target, GgrsTrackedComponentSnapshots<S::Target, S::Stored>>();
+
+        app.init_resource::<GgrsTrackedComponentSnapshots<S::Target, S::Stored>>()
+            .init_resource::<GgrsTrackedSnapshotTick<S::Target>>()
+            .add_systems(
+                SaveWorld,
+                (
+                    GgrsTrackedComponentSnapshots::<S::Target, S::Stored>::discard_old_snapshots,
+                    Self::save,
+                )
+                    .chain()
+                    .in_set(SaveWorldSet::Snapshot),
+            )
+            .add_systems(LoadWorld, Self::load.in_set(LoadWorldSet::Data));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
-    GgrsComponentSnapshot, GgrsComponentSnapshots, LoadWorld, LoadWorldSet, Rollback,
-    RollbackEntityMap, RollbackFrameCount, SaveWorld, SaveWorldSet,
+    DespawnRollbackCommandExtension, GgrsComponentSnapshot, GgrsComponentSnapshots, LoadWorld,
+    LoadWorldSet, PooledRollback, Rollback, RollbackEntityMap, RollbackEntityPool,
+    RollbackFrameCount, SaveWorld, SaveWorldSet, SnapshotMemoryRegistry,
 };
 use bevy::{ecs::entity::EntityMap, prelude::*, utils::HashMap};
 
@@ -47,8 +48,10 @@ impl EntitySnapshotPlugin {
         mut snapshots: ResMut<GgrsComponentSnapshots<Entity>>,
         mut map: ResMut<RollbackEntityMap>,
         frame: Res<RollbackFrameCount>,
+        mut pool: Option<ResMut<RollbackEntityPool>>,
         query: Query<(&Rollback, Entity)>,
     ) {
+        let mut revived = 0;
         let mut entity_map = EntityMap::default();
         let mut rollback_mapping = HashMap::new();
 
@@ -62,16 +65,29 @@ impl EntitySnapshotPlugin {
             rollback_mapping.entry(rollback).or_insert((None, None)).0 = Some(current_entity);
         }
 
-        for (current_entity, old_entity) in rollback_mapping.values() {
+        for (&rollback, (current_entity, old_entity)) in rollback_mapping.iter() {
             match (current_entity, old_entity) {
                 (Some(current_entity), Some(old_entity)) => {
                     entity_map.insert(*current_entity, *old_entity);
                 }
                 (Some(current_entity), None) => {
-                    commands.entity(*current_entity).despawn();
+                    commands.entity(*current_entity).despawn_rollback();
                 }
                 (None, Some(old_entity)) => {
-                    let current_entity = commands.spawn_empty().id();
+                    let pooled = pool.as_mut().and_then(|pool| pool.take(rollback));
+
+                    let current_entity = match pooled {
+                        Some(pooled) => {
+                            revived += 1;
+                            commands
+                                .entity(pooled)
+                                .remove::<PooledRollback>()
+                                .insert(rollback);
+                            pooled
+                        }
+                        None => commands.spawn(rollback).id(),
+                    };
+
                     entity_map.insert(*old_entity, current_entity);
                 }
                 (None, None) => unreachable!(
@@ -80,7 +96,11 @@ impl EntitySnapshotPlugin {
             }
         }
 
-        trace!("Rolled back {} entity(s)", snapshot.iter().count());
+        trace!(
+            "Rolled back {} entity(s), {} revived from the pool",
+            snapshot.iter().count(),
+            revived
+        );
 
         *map = RollbackEntityMap::new(entity_map);
     }
@@ -88,6 +108,10 @@ impl EntitySnapshotPlugin {
 
 impl Plugin for EntitySnapshotPlugin {
     fn build(&self, app: &mut App) {
+        app.world
+            .get_resource_or_insert_with(SnapshotMemoryRegistry::default)
+            .register::<Entity, GgrsComponentSnapshots<Entity>>();
+
         app.init_resource::<GgrsComponentSnapshots<Entity>>()
             .init_resource::<RollbackEntityMap>()
             .add_systems(
@@ -0,0 +1,180 @@
+use crate::{GgrsUpdateSet, MaxPredictionWindow, Rollback, RollbackFrameCount};
+use bevy::{
+    ecs::system::{EntityCommand, EntityCommands},
+    prelude::*,
+    utils::HashMap,
+};
+
+/// A marker [`Component`] for a despawned [`Rollback`] entity which is kept in the
+/// [`RollbackEntityPool`], so it can be revived if a rollback restores it. Pooled entities have
+/// no other components, but can still be matched by queries such as `Query<Entity>`, which
+/// may want to filter `Without<PooledRollback>`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PooledRollback {
+    rollback: Rollback,
+    frame: i32,
+}
+
+impl PooledRollback {
+    /// The [`Rollback`] flag this entity had before it was despawned.
+    pub fn rollback(&self) -> Rollback {
+        self.rollback
+    }
+
+    /// The frame this entity was despawned in.
+    pub fn frame(&self) -> i32 {
+        self.frame
+    }
+}
+
+/// A [`Resource`] holding [`Rollback`] entities which were despawned using
+/// [`despawn_rollback`](`DespawnRollbackCommandExtension::despawn_rollback`) within the
+/// [`MaxPredictionWindow`]. If a rollback restores one of these entities, the
+/// [`EntitySnapshotPlugin`](`crate::EntitySnapshotPlugin`) revives it rather than spawning a
+/// new [`Entity`], so it does not need to be remapped.
+#[derive(Resource, Default)]
+pub struct RollbackEntityPool {
+    pooled: HashMap<Rollback, Entity>,
+}
+
+impl RollbackEntityPool {
+    /// The number of entities currently pooled.
+    pub fn len(&self) -> usize {
+        self.pooled.len()
+    }
+
+    /// Returns `true` if no entities are pooled, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.pooled.is_empty()
+    }
+
+    /// Returns the pooled [`Entity`] for the provided [`Rollback`], if any.
+    pub fn get(&self, rollback: Rollback) -> Option<Entity> {
+        self.pooled.get(&rollback).copied()
+    }
+
+    /// Remove and return the pooled [`Entity`] for the provided [`Rollback`], if any.
+    pub(crate) fn take(&mut self, rollback: Rollback) -> Option<Entity> {
+        self.pooled.remove(&rollback)
+    }
+
+    fn insert(&mut self, rollback: Rollback, entity: Entity) {
+        self.pooled.insert(rollback, entity);
+    }
+}
+
+/// An `EntityCommand` which despawns a [`Rollback`] entity, or moves it into the
+/// [`RollbackEntityPool`] if one exists. Pooled entities are removed from the [`Children`] of
+/// their parent, as if they had been despawned.
+pub struct DespawnRollbackCommand;
+
+impl EntityCommand for DespawnRollbackCommand {
+    fn apply(self, id: Entity, world: &mut World) {
+        let rollback = world.get::<Rollback>(id).copied();
+
+        let (Some(rollback), true) = (rollback, world.contains_resource::<RollbackEntityPool>())
+        else {
+            world.despawn(id);
+            return;
+        };
+
+        let frame = world
+            .get_resource::<RollbackFrameCount>()
+            .map(|frame| frame.0)
+            .unwrap_or_default();
+
+        world
+            .entity_mut(id)
+            .remove_parent()
+            .retain::<()>()
+            .insert(PooledRollback { rollback, frame });
+
+        world
+            .resource_mut::<RollbackEntityPool>()
+            .insert(rollback, id);
+    }
+}
+
+mod private {
+    /// Private seal to ensure `DespawnRollbackCommandExtension` cannot be implemented by crate consumers.
+    pub trait DespawnRollbackCommandExtensionSeal {}
+}
+
+/// Extension trait for `EntityCommands` which adds the `despawn_rollback()` method.
+pub trait DespawnRollbackCommandExtension: private::DespawnRollbackCommandExtensionSeal {
+    /// Despawns this [`Rollback`] `Entity`. If the [`RollbackEntityPoolPlugin`] was added,
+    /// the `Entity` is pooled instead, so it can be revived if a rollback restores it.
+    fn despawn_rollback(&mut self);
+}
+
+impl<'w, 's, 'a> private::DespawnRollbackCommandExtensionSeal for EntityCommands<'w, 's, 'a> {}
+
+impl<'w, 's, 'a> DespawnRollbackCommandExtension for EntityCommands<'w, 's, 'a> {
+    fn despawn_rollback(&mut self) {
+        self.add(DespawnRollbackCommand);
+    }
+}
+
+/// A [`Plugin`] which keeps [`Rollback`] entities despawned using
+/// [`despawn_rollback`](`DespawnRollbackCommandExtension::despawn_rollback`) in a
+/// [`RollbackEntityPool`] for up to [`MaxPredictionWindow`] frames. Entities which reappear
+/// after a rollback are revived with the same [`Entity`], avoiding the cost of spawning and
+/// remapping them.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, RollbackEntityPoolPlugin};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// app.add_plugins(RollbackEntityPoolPlugin);
+///
+/// #[derive(Component, Clone, Copy)]
+/// struct Lifetime(u32);
+///
+/// fn expire_projectiles(mut commands: Commands, query: Query<(Entity, &Lifetime)>) {
+///     for (entity, lifetime) in query.iter() {
+///         if lifetime.0 == 0 {
+///             commands.entity(entity).despawn_rollback();
+///         }
+///     }
+/// }
+///
+/// app.add_systems(GgrsSchedule, expire_projectiles);
+/// # }
+/// ```
+pub struct RollbackEntityPoolPlugin;
+
+impl RollbackEntityPoolPlugin {
+    /// A [`System`] which despawns pooled entities which can no longer be restored by a rollback.
+    /// This runs after every update, as sessions which never roll back (such as spectators and
+    /// replays) do not save snapshots.
+    pub fn expire(
+        mut commands: Commands,
+        mut pool: ResMut<RollbackEntityPool>,
+        frame: Res<RollbackFrameCount>,
+        max_prediction: Res<MaxPredictionWindow>,
+        query: Query<(Entity, &PooledRollback)>,
+    ) {
+        let oldest = frame.0 - max_prediction.0 as i32;
+
+        for (entity, pooled) in query.iter() {
+            if pooled.frame >= oldest {
+                continue;
+            }
+
+            pool.take(pooled.rollback);
+            commands.entity(entity).despawn();
+        }
+
+        trace!("{} pooled entity(s)", pool.len());
+    }
+}
+
+impl Plugin for RollbackEntityPoolPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RollbackEntityPool>()
+            .add_systems(PreUpdate, Self::expire.after(GgrsUpdateSet));
+    }
+}
@@ -0,0 +1,417 @@
+use crate::{
+    ComponentMapEntitiesPlugin, ComponentSnapshotPlugin, LoadWorld, LoadWorldSet, PooledRollback,
+    ReflectStrategy, Rollback,
+};
+use bevy::{
+    ecs::entity::MapEntities,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::marker::PhantomData;
+
+/// A relationship between entities which can be rolled back by a [`RelationshipSnapshotPlugin`].
+///
+/// The relationship is stored as this [`Component`] on every child, pointing at its parent, and as
+/// a [`Children`](`RollbackRelationship::Children`) [`Component`] on the parent, listing its
+/// children. [`Parent`] and [`Children`] are the typical example.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::{prelude::*, ecs::entity::{MapEntities, EntityMapper}};
+/// # use bevy_ggrs::{prelude::*, RelationshipSnapshotPlugin, RollbackRelationship};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// #[derive(Component, Reflect, Clone, Copy)]
+/// struct Tether(Entity);
+///
+/// impl FromWorld for Tether {
+///     fn from_world(_world: &mut World) -> Self {
+///         Self(Entity::PLACEHOLDER)
+///     }
+/// }
+///
+/// impl MapEntities for Tether {
+///     fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+///         self.0 = entity_mapper.get_or_reserve(self.0);
+///     }
+/// }
+///
+/// #[derive(Component, Reflect, Default, Clone)]
+/// struct Tethered(Vec<Entity>);
+///
+/// impl MapEntities for Tethered {
+///     fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+///         for entity in &mut self.0 {
+///             *entity = entity_mapper.get_or_reserve(*entity);
+///         }
+///     }
+/// }
+///
+/// impl RollbackRelationship for Tether {
+///     type Children = Tethered;
+///
+///     fn parent(&self) -> Entity {
+///         self.0
+///     }
+///
+///     fn children(children: &Tethered) -> &[Entity] {
+///         &children.0
+///     }
+///
+///     fn set_children(world: &mut World, parent: Entity, children: &[Entity]) {
+///         if children.is_empty() {
+///             world.entity_mut(parent).remove::<Tethered>();
+///         } else {
+///             world.entity_mut(parent).insert(Tethered(children.to_vec()));
+///         }
+///     }
+/// }
+///
+/// app.add_plugins(RelationshipSnapshotPlugin::<Tether>::default());
+/// # }
+/// ```
+pub trait RollbackRelationship: Component + Reflect + FromWorld + MapEntities {
+    /// The [`Component`] on a parent listing its children.
+    type Children: Component + Reflect + FromWorld + MapEntities;
+
+    /// The parent this child is related to.
+    fn parent(&self) -> Entity;
+
+    /// The children listed by a parent.
+    fn children(children: &Self::Children) -> &[Entity];
+
+    /// Replace the [`Children`](`RollbackRelationship::Children`) of `parent` with `children`,
+    /// removing the [`Component`] entirely if there are none.
+    fn set_children(world: &mut World, parent: Entity, children: &[Entity]);
+}
+
+impl RollbackRelationship for Parent {
+    type Children = Children;
+
+    fn parent(&self) -> Entity {
+        self.get()
+    }
+
+    fn children(children: &Children) -> &[Entity] {
+        children
+    }
+
+    fn set_children(world: &mut World, parent: Entity, children: &[Entity]) {
+        let mut parent = world.entity_mut(parent);
+        parent.remove::<Children>();
+
+        if !children.is_empty() {
+            parent.push_children(children);
+        }
+    }
+}
+
+/// A [`Plugin`] which rolls back a [`RollbackRelationship`] between [`Rollback`] entities.
+///
+/// After the relationship has been loaded and mapped, it is repaired to be consistent with the
+/// [`World`]:
+/// - The [`Children`](`RollbackRelationship::Children`) of every parent are rebuilt from the
+///   relationship of its living children, so non-rollback children of rollback entities are
+///   preserved.
+/// - Non-rollback descendants of rollback entities which no longer exist are despawned
+///   recursively, matching [`despawn_recursive`](`DespawnRecursiveExt::despawn_recursive`).
+///
+/// Only [`Rollback`] entities and their direct relatives are repaired, so the cost does not grow
+/// with unrelated hierarchies such as UI.
+///
+/// See [`RollbackRelationship`] for an example.
+pub struct RelationshipSnapshotPlugin<R>
+where
+    R: RollbackRelationship,
+{
+    _phantom: PhantomData<R>,
+}
+
+impl<R> Default for RelationshipSnapshotPlugin<R>
+where
+    R: RollbackRelationship,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: default(),
+        }
+    }
+}
+
+impl<R> RelationshipSnapshotPlugin<R>
+where
+    R: RollbackRelationship,
+{
+    /// A [`System`] which records the relatives of every [`Rollback`] entity before a snapshot is
+    /// loaded, so they can be repaired once it has been.
+    pub fn record_relatives(
+        mut relatives: ResMut<RelationshipRelatives<R>>,
+        query: Query<(Option<&R>, Option<&R::Children>), With<Rollback>>,
+    ) {
+        relatives.entities.clear();
+
+        for (parent, children) in query.iter() {
+            relatives.entities.extend(parent.map(R::parent));
+            relatives
+                .entities
+                .extend(children.into_iter().flat_map(R::children));
+        }
+    }
+
+    /// Exclusive system which makes the relationship `R` consistent after a rollback.
+    /// Only [`Rollback`] entities and their direct relatives, before and after loading the
+    /// snapshot, are considered.
+    pub fn repair(world: &mut World) {
+        let mut rollbacks =
+            world.query_filtered::<(Entity, Option<&R>, Option<&R::Children>), With<Rollback>>();
+
+        let mut related =
+            std::mem::take(&mut world.resource_mut::<RelationshipRelatives<R>>().entities)
+                .into_iter()
+                .collect::<HashSet<_>>();
+
+        for (entity, parent, children) in rollbacks.iter(world) {
+            related.insert(entity);
+            related.extend(parent.map(R::parent));
+            related.extend(children.into_iter().flat_map(R::children));
+        }
+
+        let mut adopted = HashMap::<Entity, Vec<Entity>>::new();
+        let mut parents = HashSet::new();
+        let mut orphans = Vec::new();
+
+        for &entity in &related {
+            let Some(entity_ref) = world.get_entity(entity) else {
+                continue;
+            };
+
+            if entity_ref.contains::<R::Children>() {
+                parents.insert(entity);
+            }
+
+            let Some(parent) = entity_ref.get::<R>().map(R::parent) else {
+                continue;
+            };
+
+            let Some(parent_ref) = world.get_entity(parent) else {
+                orphans.push((entity, entity_ref.contains::<Rollback>()));
+                continue;
+            };
+
+            // Pooled entities are considered despawned, but may still list their children
+            if parent_ref.contains::<PooledRollback>() {
+                orphans.push((entity, entity_ref.contains::<Rollback>()));
+            } else {
+                adopted.entry(parent).or_default().push(entity);
+            }
+
+            parents.insert(parent);
+        }
+
+        for (entity, is_rollback) in orphans {
+            if is_rollback {
+                world.entity_mut(entity).remove::<R>();
+            } else {
+                despawn_with_children::<R>(world, entity);
+            }
+        }
+
+        let mut repaired = 0;
+
+        for parent in parents {
+            if world.get_entity(parent).is_none() {
+                continue;
+            }
+
+            let current = world
+                .get::<R::Children>(parent)
+                .map(|children| R::children(children).to_vec())
+                .unwrap_or_default();
+
+            // Preserve the order of the restored children, dropping those which no longer exist
+            // or have moved to another parent
+            let mut children = current
+                .iter()
+                .copied()
+                .filter(|&child| {
+                    world
+                        .get::<R>(child)
+                        .is_some_and(|child_parent| child_parent.parent() == parent)
+                })
+                .collect::<Vec<_>>();
+
+            let mut missing = adopted.remove(&parent).unwrap_or_default();
+            missing.retain(|child| !children.contains(child));
+            missing.sort();
+            children.extend(missing);
+
+            if children == current {
+                continue;
+            }
+
+            repaired += 1;
+
+            R::set_children(world, parent, &children);
+        }
+
+        trace!(
+            "Repaired the {} relationship of {repaired} entity(s)",
+            bevy::utils::get_short_name(std::any::type_name::<R>())
+        );
+    }
+}
+
+/// Despawn an [`Entity`] and all of its descendants through the relationship `R`.
+fn despawn_with_children<R: RollbackRelationship>(world: &mut World, entity: Entity) {
+    let children = world
+        .get::<R::Children>(entity)
+        .map(|children| R::children(children).to_vec())
+        .unwrap_or_default();
+
+    for child in children {
+        if world.get_entity(child).is_some() {
+            despawn_with_children::<R>(world, child);
+        }
+    }
+
+    world.despawn(entity);
+}
+
+/// The relatives of every [`Rollback`] entity through the relationship `R` before a snapshot was
+/// loaded, recorded by [`RelationshipSnapshotPlugin::record_relatives`].
+#[derive(Resource)]
+pub struct RelationshipRelatives<R> {
+    entities: Vec<Entity>,
+    _phantom: PhantomData<R>,
+}
+
+impl<R> Default for RelationshipRelatives<R> {
+    fn default() -> Self {
+        Self {
+            entities: default(),
+            _phantom: default(),
+        }
+    }
+}
+
+impl<R> Plugin for RelationshipSnapshotPlugin<R>
+where
+    R: RollbackRelationship,
+{
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ComponentSnapshotPlugin::<ReflectStrategy<R>>::default(),
+            ComponentMapEntitiesPlugin::<R>::default(),
+            ComponentSnapshotPlugin::<ReflectStrategy<R::Children>>::default(),
+            ComponentMapEntitiesPlugin::<R::Children>::default(),
+        ))
+        .init_resource::<RelationshipRelatives<R>>()
+        .add_systems(
+            LoadWorld,
+            (
+                Self::record_relatives.before(LoadWorldSet::Entity),
+                Self::repair
+                    .in_set(LoadWorldSet::Mapping)
+                    .after(ComponentMapEntitiesPlugin::<R>::update)
+                    .after(ComponentMapEntitiesPlugin::<R::Children>::update),
+            ),
+        );
+    }
+}
+
+/// A [`Plugin`] which rolls back the [`Parent`] and [`Children`] hierarchy of [`Rollback`]
+/// entities using a [`RelationshipSnapshotPlugin`]. This is added by
+/// [`GgrsPlugin`](`crate::GgrsPlugin`) unless disabled with
+/// [`without_hierarchy_rollback`](`crate::GgrsPlugin::without_hierarchy_rollback`).
+///
+/// Once the hierarchy has been repaired, the [`GlobalTransform`] of every hierarchy containing a
+/// [`Rollback`] entity is propagated from the restored [`Transform`]s, so systems in the
+/// [`GgrsSchedule`](`crate::GgrsSchedule`) observe the restored positions. Other hierarchies are
+/// left to Bevy's [`TransformPlugin`].
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, RelationshipSnapshotPlugin};
+/// #
+/// # type MyInputType = u8;
+/// #
+/// # fn start(session: Session<GgrsConfig<MyInputType>>) {
+/// # let mut app = App::new();
+/// // Hierarchy rollback is provided by default, but can be opted out of
+/// app.add_plugins(GgrsPlugin::<GgrsConfig<MyInputType>>::default().without_hierarchy_rollback());
+///
+/// // For example, to roll back the hierarchy without propagating transforms
+/// app.add_plugins(RelationshipSnapshotPlugin::<Parent>::default());
+/// # }
+/// ```
+pub struct HierarchySnapshotPlugin;
+
+impl HierarchySnapshotPlugin {
+    /// Exclusive system which propagates the [`GlobalTransform`] of every hierarchy containing a
+    /// [`Rollback`] entity. Entities with a [`Transform`] but no [`GlobalTransform`], such as
+    /// those recreated by a rollback, are given one.
+    pub fn propagate_transforms(world: &mut World) {
+        let mut rollbacks = world.query_filtered::<Entity, With<Rollback>>();
+
+        let mut roots = HashSet::new();
+
+        for mut entity in rollbacks.iter(world) {
+            while let Some(parent) = world.get::<Parent>(entity) {
+                entity = parent.get();
+            }
+
+            roots.insert(entity);
+        }
+
+        let mut pending = roots
+            .into_iter()
+            .map(|root| (root, None))
+            .collect::<Vec<(Entity, Option<GlobalTransform>)>>();
+
+        let mut propagated = 0;
+
+        while let Some((entity, parent_global)) = pending.pop() {
+            let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+                continue;
+            };
+
+            // Like Bevy's own propagation, a missing Transform breaks the hierarchy
+            let Some(&transform) = entity_mut.get::<Transform>() else {
+                continue;
+            };
+
+            let global = match parent_global {
+                Some(parent_global) => parent_global.mul_transform(transform),
+                None => GlobalTransform::from(transform),
+            };
+
+            if let Some(mut current) = entity_mut.get_mut::<GlobalTransform>() {
+                current.set_if_neq(global);
+            } else {
+                entity_mut.insert(global);
+            }
+
+            propagated += 1;
+
+            if let Some(children) = entity_mut.get::<Children>() {
+                pending.extend(children.iter().map(|&child| (child, Some(global))));
+            }
+        }
+
+        trace!("Propagated the global transform of {propagated} entity(s)");
+    }
+}
+
+impl Plugin for HierarchySnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RelationshipSnapshotPlugin::<Parent>::default())
+            .add_systems(
+                LoadWorld,
+                Self::propagate_transforms
+                    .in_set(LoadWorldSet::Mapping)
+                    .after(RelationshipSnapshotPlugin::<Parent>::repair),
+            );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{GgrsComponentSnapshot, GgrsSnapshots, Rollback};
+use bevy::prelude::*;
+use std::mem::size_of;
+
+/// Estimates the memory used by stored snapshots, for use with
+/// [`GgrsDiagnosticsPlugin`](`crate::GgrsDiagnosticsPlugin`).
+///
+/// This is a shallow estimate: memory owned by a stored value (such as the contents of a `Vec`)
+/// and the overhead of the collections themselves are not included.
+pub trait SnapshotMemory {
+    /// The estimated number of bytes used.
+    fn memory_usage(&self) -> usize;
+}
+
+impl<For, As> SnapshotMemory for GgrsComponentSnapshot<For, As> {
+    fn memory_usage(&self) -> usize {
+        self.snapshot.len() * size_of::<(Rollback, As)>()
+    }
+}
+
+impl<As> SnapshotMemory for Option<As> {
+    fn memory_usage(&self) -> usize {
+        self.as_ref().map_or(0, |_| size_of::<As>())
+    }
+}
+
+impl<For, As> SnapshotMemory for GgrsSnapshots<For, As>
+where
+    As: SnapshotMemory,
+{
+    fn memory_usage(&self) -> usize {
+        self.snapshots
+            .iter()
+            .map(SnapshotMemory::memory_usage)
+            .sum::<usize>()
+            + self.frames.len() * size_of::<i32>()
+    }
+}
+
+/// A [`Resource`] listing every snapshot [`Resource`] which implements [`SnapshotMemory`], so
+/// their memory usage can be measured without knowing their types. Snapshot plugins provided by
+/// this crate register themselves automatically.
+#[derive(Resource, Default)]
+pub struct SnapshotMemoryRegistry {
+    entries: Vec<(&'static str, fn(&World) -> usize)>,
+}
+
+impl SnapshotMemoryRegistry {
+    /// Register the snapshot [`Resource`] `R`, reported under the name of the type `For`.
+    pub fn register<For, R>(&mut self) -> &mut Self
+    where
+        R: Resource + SnapshotMemory,
+    {
+        let name = std::any::type_name::<For>();
+
+        if !self
+            .entries
+            .iter()
+            .any(|&(registered, _)| registered == name)
+        {
+            self.entries.push((name, measure::<R>));
+        }
+
+        self
+    }
+
+    /// The names of all registered snapshots.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.iter().map(|&(name, _)| name)
+    }
+
+    /// Measure the memory usage of every registered snapshot in the provided [`World`].
+    pub fn measure<'a>(
+        &'a self,
+        world: &'a World,
+    ) -> impl Iterator<Item = (&'static str, usize)> + 'a {
+        self.entries
+            .iter()
+            .map(move |&(name, measure)| (name, measure(world)))
+    }
+}
+
+fn measure<R>(world: &World) -> usize
+where
+    R: Resource + SnapshotMemory,
+{
+    world
+        .get_resource::<R>()
+        .map_or(0, SnapshotMemory::memory_usage)
+}
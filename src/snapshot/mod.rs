@@ -3,28 +3,46 @@ use bevy::{prelude::*, utils::HashMap};
 use std::{collections::VecDeque, marker::PhantomData};
 
 mod checksum;
+mod checksum_report;
 mod component_checksum_hash;
+mod component_delta_snapshot;
 mod component_map;
 mod component_snapshot;
+mod component_tracked_snapshot;
 mod entity;
+mod entity_pool;
+mod hierarchy;
+mod memory;
 mod resource_checksum_hash;
 mod resource_map;
 mod resource_snapshot;
 mod rollback_entity_map;
+mod rolled_back;
 mod set;
 mod strategy;
+mod sync_test_report;
+mod world_snapshot;
 
 pub use checksum::*;
+pub use checksum_report::*;
 pub use component_checksum_hash::*;
+pub use component_delta_snapshot::*;
 pub use component_map::*;
 pub use component_snapshot::*;
+pub use component_tracked_snapshot::*;
 pub use entity::*;
+pub use entity_pool::*;
+pub use hierarchy::*;
+pub use memory::*;
 pub use resource_checksum_hash::*;
 pub use resource_map::*;
 pub use resource_snapshot::*;
 pub use rollback_entity_map::*;
+pub use rolled_back::*;
 pub use set::*;
 pub use strategy::*;
+pub use sync_test_report::*;
+pub use world_snapshot::*;
 
 pub mod prelude {
     pub use super::{Checksum, LoadWorldSet, SaveWorldSet};
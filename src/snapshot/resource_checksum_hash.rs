@@ -2,7 +2,10 @@ use std::{hash::Hash, marker::PhantomData};
 
 use bevy::prelude::*;
 
-use crate::{ChecksumFlag, ChecksumPart, Rollback, SaveWorld, SaveWorldSet};
+use crate::{
+    ChecksumFlag, ChecksumHistory, ChecksumPart, ChecksumPartDetails, Rollback, SaveWorld,
+    SaveWorldSet,
+};
 
 /// Plugin which will track the [`Resource`] `R` and ensure a [`ChecksumPart`] is
 /// available and updated. This can be used to generate a [`Checksum`](`crate::Checksum`).
@@ -53,10 +56,18 @@ where
     R: Resource + Hash,
 {
     /// A [`System`] responsible for managing a [`ChecksumPart`] for the [`Resource`] type `R`.
+    ///
+    /// While a [`ChecksumHistory`] is present, a [`ChecksumPartDetails`] naming `R` is also
+    /// maintained alongside the [`ChecksumPart`].
+    #[allow(clippy::type_complexity)]
     pub fn update(
         mut commands: Commands,
         resource: Res<R>,
-        mut checksum: Query<&mut ChecksumPart, (Without<Rollback>, With<ChecksumFlag<R>>)>,
+        history: Option<Res<ChecksumHistory>>,
+        mut checksum: Query<
+            (Entity, &mut ChecksumPart, Option<&ChecksumPartDetails>),
+            (Without<Rollback>, With<ChecksumFlag<R>>),
+        >,
     ) {
         let result = ChecksumPart::from_value(resource.as_ref());
 
@@ -66,10 +77,25 @@ where
             result.0
         );
 
-        if let Ok(mut checksum) = checksum.get_single_mut() {
-            *checksum = result;
-        } else {
-            commands.spawn((result, ChecksumFlag::<R>::default()));
+        let details = history.is_some().then(|| ChecksumPartDetails {
+            name: std::any::type_name::<R>(),
+            entities: Vec::new(),
+        });
+
+        match (checksum.get_single_mut(), details) {
+            (Ok((entity, mut checksum, current)), details) => {
+                *checksum = result;
+
+                if let (None, Some(details)) = (current, details) {
+                    commands.entity(entity).insert(details);
+                }
+            }
+            (Err(_), Some(details)) => {
+                commands.spawn((result, ChecksumFlag::<R>::default(), details));
+            }
+            (Err(_), None) => {
+                commands.spawn((result, ChecksumFlag::<R>::default()));
+            }
         }
     }
 }
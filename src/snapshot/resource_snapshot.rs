@@ -1,6 +1,6 @@
 use crate::{
     GgrsResourceSnapshots, LoadWorld, LoadWorldSet, RollbackFrameCount, SaveWorld, SaveWorldSet,
-    Strategy,
+    SnapshotMemoryRegistry, SnapshotReflectRegistry, Strategy,
 };
 use bevy::prelude::*;
 use std::marker::PhantomData;
@@ -97,6 +97,14 @@ where
     S::Stored: Send + Sync + 'static,
 {
     fn build(&self, app: &mut App) {
+        app.world
+            .get_resource_or_insert_with(SnapshotReflectRegistry::default)
+            .register_resource::<S>();
+
+        app.world
+            .get_resource_or_insert_with(SnapshotMemoryRegistry::default)
+            .register::<S::Target, GgrsResourceSnapshots<S::Target, S::Stored>>();
+
         app.init_resource::<GgrsResourceSnapshots<S::Target, S::Stored>>()
             .add_systems(
                 SaveWorld,
@@ -0,0 +1,137 @@
+use bevy::{
+    ecs::{change_detection::DetectChanges, component::Tick},
+    prelude::*,
+};
+use std::marker::PhantomData;
+
+/// A marker [`Component`] recording when a [`Component`] `T` was last written by loading a
+/// snapshot. As loading a snapshot triggers change detection, this can be used to distinguish
+/// changes made by the simulation from changes made by a rollback.
+///
+/// This is opt-in for each type through [`track_rolled_back`](`crate::GgrsApp::track_rolled_back`),
+/// and maintained by [`ComponentSnapshotPlugin`](`crate::ComponentSnapshotPlugin`),
+/// [`DeltaComponentSnapshotPlugin`](`crate::DeltaComponentSnapshotPlugin`) and
+/// [`TrackedComponentSnapshotPlugin`](`crate::TrackedComponentSnapshotPlugin`).
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, RolledBack};
+/// #
+/// # #[derive(Component, Clone, Copy)]
+/// # struct Collider;
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// app.rollback_component_with_copy::<Collider>()
+///     .track_rolled_back::<Collider>();
+/// # }
+///
+/// fn update_spatial_index(query: Query<(Ref<Collider>, Option<&RolledBack<Collider>>)>) {
+///     for (collider, rolled_back) in query.iter() {
+///         if RolledBack::is_simulation_change(&collider, rolled_back) {
+///             // ...
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component)]
+pub struct RolledBack<T: Component> {
+    tick: Tick,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Component> RolledBack<T> {
+    pub(crate) fn new(tick: Tick) -> Self {
+        Self {
+            tick,
+            _phantom: default(),
+        }
+    }
+
+    pub(crate) fn set_tick(&mut self, tick: Tick) -> &mut Self {
+        self.tick = tick;
+        self
+    }
+
+    /// The change tick at which `T` was last written by loading a snapshot.
+    pub fn tick(&self) -> Tick {
+        self.tick
+    }
+
+    /// Returns `true` if the most recent change to the provided component was made by loading
+    /// a snapshot, `false` otherwise.
+    pub fn is_restoration(&self, component: &impl DetectChanges) -> bool {
+        component.last_changed() == self.tick
+    }
+
+    /// Returns `true` if the provided component was changed since the system last ran, and
+    /// that change was not made by loading a snapshot.
+    pub fn is_simulation_change(
+        component: &impl DetectChanges,
+        rolled_back: Option<&Self>,
+    ) -> bool {
+        component.is_changed()
+            && !rolled_back.is_some_and(|rolled_back| rolled_back.is_restoration(component))
+    }
+}
+
+/// A [`Resource`] which enables the [`RolledBack`] marker for the [`Component`] `T`. Added by
+/// [`track_rolled_back`](`crate::GgrsApp::track_rolled_back`).
+#[derive(Resource)]
+pub struct TrackRolledBack<T: Component> {
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Component> Default for TrackRolledBack<T> {
+    fn default() -> Self {
+        Self {
+            _phantom: default(),
+        }
+    }
+}
+
+/// Insert a restored component, along with a [`RolledBack`] marker sharing its change tick if
+/// the marker is tracked.
+pub(crate) fn insert_rolled_back<T: Component>(
+    commands: &mut Commands,
+    entity: Entity,
+    component: T,
+    track_marker: bool,
+) {
+    if !track_marker {
+        commands.entity(entity).insert(component);
+        return;
+    }
+
+    commands.add(move |world: &mut World| {
+        let tick = world.change_tick();
+
+        if let Some(mut entity) = world.get_entity_mut(entity) {
+            entity.insert((component, RolledBack::<T>::new(tick)));
+        }
+    });
+}
+
+/// Mark a component updated in place by a snapshot, using the change tick of the current system.
+/// Does nothing if the marker is not tracked.
+pub(crate) fn mark_rolled_back<T: Component>(
+    commands: &mut Commands,
+    entity: Entity,
+    marker: Option<Mut<RolledBack<T>>>,
+    tick: Tick,
+    track_marker: bool,
+) {
+    if !track_marker {
+        return;
+    }
+
+    match marker {
+        Some(mut marker) => {
+            marker.set_tick(tick);
+        }
+        None => {
+            commands.entity(entity).insert(RolledBack::<T>::new(tick));
+        }
+    }
+}
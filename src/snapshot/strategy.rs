@@ -26,6 +26,12 @@ pub trait Strategy {
     fn update(target: &mut Self::Target, stored: &Self::Stored) {
         *target = Self::load(stored);
     }
+
+    /// Optionally provide a [`Reflect`] view of a [`Stored`](`Strategy::Stored`) value,
+    /// allowing it to be inspected and serialized, see [`WorldSnapshot`](`crate::WorldSnapshot`).
+    fn as_reflect(_stored: &Self::Stored) -> Option<&dyn Reflect> {
+        None
+    }
 }
 
 /// A [`Strategy`] based on [`Copy`]
@@ -91,4 +97,9 @@ impl<T: Reflect + FromWorld> Strategy for ReflectStrategy<T> {
         Self::update(&mut target, stored);
         target
     }
+
+    #[inline(always)]
+    fn as_reflect(stored: &Self::Stored) -> Option<&dyn Reflect> {
+        Some(stored.as_ref())
+    }
 }
@@ -0,0 +1,399 @@
+use crate::{
+    Checksum, ChecksumHistory, ChecksumMismatch, ChecksumReport, GgrsComponentSnapshots, Rollback,
+    RollbackFrameCount, SaveWorld, SaveWorldSet, SnapshotReflectRegistry, DEFAULT_FPS,
+};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::{collections::BTreeSet, fmt};
+
+/// The state of a single frame, as recorded by [`SyncTestReportPlugin`].
+struct SyncTestFrame {
+    checksum: Option<u128>,
+    entities: HashMap<Rollback, Vec<Box<dyn Reflect>>>,
+    resources: Vec<Box<dyn Reflect>>,
+    report: Option<ChecksumReport>,
+}
+
+impl SyncTestFrame {
+    fn capture(world: &World, frame: i32) -> Self {
+        let mut entities = world
+            .get_resource::<GgrsComponentSnapshots<Entity>>()
+            .and_then(|snapshots| snapshots.peek(frame))
+            .map(|snapshot| {
+                snapshot
+                    .iter()
+                    .map(|(&rollback, _)| (rollback, Vec::new()))
+                    .collect::<HashMap<_, _>>()
+            })
+            .unwrap_or_default();
+
+        let mut resources = Vec::new();
+
+        if let Some(registry) = world.get_resource::<SnapshotReflectRegistry>() {
+            let captured = registry.capture(world, frame);
+
+            for (rollback, components) in captured.components {
+                if let Some(entity) = entities.get_mut(&rollback) {
+                    *entity = components;
+                }
+            }
+
+            resources = captured.resources;
+        }
+
+        Self {
+            checksum: world
+                .get_resource::<Checksum>()
+                .map(|&Checksum(checksum)| checksum),
+            entities,
+            resources,
+            report: world
+                .get_resource::<ChecksumHistory>()
+                .and_then(|history| history.get(frame))
+                .cloned(),
+        }
+    }
+
+    /// Compare this (original) frame against its resimulated counterpart.
+    fn diff(&self, resimulated: &Self) -> Vec<SnapshotDifference> {
+        let mut differences = Vec::new();
+
+        let rollbacks = self
+            .entities
+            .keys()
+            .chain(resimulated.entities.keys())
+            .copied()
+            .collect::<BTreeSet<_>>();
+
+        for rollback in rollbacks {
+            match (
+                self.entities.get(&rollback),
+                resimulated.entities.get(&rollback),
+            ) {
+                (Some(original), Some(current)) => {
+                    differences.extend(diff_values(original, current).into_iter().map(
+                        |(type_name, original, resimulated)| SnapshotDifference::Component {
+                            rollback,
+                            type_name,
+                            original,
+                            resimulated,
+                        },
+                    ));
+                }
+                (original, _) => differences.push(SnapshotDifference::Entity {
+                    rollback,
+                    original: original.is_some(),
+                }),
+            }
+        }
+
+        differences.extend(
+            diff_values(&self.resources, &resimulated.resources)
+                .into_iter()
+                .map(
+                    |(type_name, original, resimulated)| SnapshotDifference::Resource {
+                        type_name,
+                        original,
+                        resimulated,
+                    },
+                ),
+        );
+
+        differences
+    }
+}
+
+type ValueDifference = (String, Option<Box<dyn Reflect>>, Option<Box<dyn Reflect>>);
+
+fn diff_values(
+    original: &[Box<dyn Reflect>],
+    resimulated: &[Box<dyn Reflect>],
+) -> Vec<ValueDifference> {
+    let by_name = |values: &[Box<dyn Reflect>]| {
+        values
+            .iter()
+            .map(|value| (value.type_name().to_owned(), value.clone_value()))
+            .collect::<HashMap<_, _>>()
+    };
+
+    let mut original = by_name(original);
+    let mut resimulated = by_name(resimulated);
+
+    let names = original
+        .keys()
+        .chain(resimulated.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let original = original.remove(&name);
+            let resimulated = resimulated.remove(&name);
+
+            let equal = match (&original, &resimulated) {
+                (Some(original), Some(resimulated)) => {
+                    original.reflect_partial_eq(resimulated.as_ref()) == Some(true)
+                }
+                _ => false,
+            };
+
+            (!equal).then_some((name, original, resimulated))
+        })
+        .collect()
+}
+
+/// A single difference between the original and resimulated state of a frame.
+#[derive(Debug)]
+pub enum SnapshotDifference {
+    /// A [`Rollback`] entity which only exists in one of the simulations.
+    Entity {
+        rollback: Rollback,
+        /// `true` if the entity only exists in the original simulation, `false` if it only
+        /// exists in the resimulation.
+        original: bool,
+    },
+    /// A [`Component`] which differs, or only exists in one of the simulations.
+    Component {
+        rollback: Rollback,
+        type_name: String,
+        original: Option<Box<dyn Reflect>>,
+        resimulated: Option<Box<dyn Reflect>>,
+    },
+    /// A [`Resource`] which differs, or only exists in one of the simulations.
+    Resource {
+        type_name: String,
+        original: Option<Box<dyn Reflect>>,
+        resimulated: Option<Box<dyn Reflect>>,
+    },
+}
+
+struct DisplayValue<'a>(&'a Option<Box<dyn Reflect>>);
+
+impl<'a> fmt::Display for DisplayValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{value:?}"),
+            None => write!(f, "<missing>"),
+        }
+    }
+}
+
+impl fmt::Display for SnapshotDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotDifference::Entity { rollback, original } => {
+                let simulation = if *original {
+                    "original simulation"
+                } else {
+                    "resimulation"
+                };
+                write!(f, "{rollback:?} only exists in the {simulation}")
+            }
+            SnapshotDifference::Component {
+                rollback,
+                type_name,
+                original,
+                resimulated,
+            } => write!(
+                f,
+                "{type_name} on {rollback:?}: original {}, resimulated {}",
+                DisplayValue(original),
+                DisplayValue(resimulated)
+            ),
+            SnapshotDifference::Resource {
+                type_name,
+                original,
+                resimulated,
+            } => write!(
+                f,
+                "{type_name}: original {}, resimulated {}",
+                DisplayValue(original),
+                DisplayValue(resimulated)
+            ),
+        }
+    }
+}
+
+/// Sent by [`SyncTestReportPlugin`] when a [`SyncTestSession`](`ggrs::SyncTestSession`) detects
+/// a checksum mismatch, describing how the resimulated frame differs from the original.
+#[derive(Event, Debug)]
+pub struct SyncTestMismatch {
+    pub frame: i32,
+    pub original_checksum: Option<u128>,
+    pub resimulated_checksum: Option<u128>,
+    /// Differences between all snapshots which can be viewed through [`Reflect`].
+    pub differences: Vec<SnapshotDifference>,
+    /// Differences between [`ChecksumReports`](`ChecksumReport`), which also cover types without
+    /// [`Reflect`] snapshots. Only available if the
+    /// [`ChecksumReportPlugin`](`crate::ChecksumReportPlugin`) was added.
+    pub checksum_mismatches: Vec<ChecksumMismatch>,
+}
+
+impl fmt::Display for SyncTestMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SyncTest mismatch on frame {}: original {:X?}, resimulated {:X?}",
+            self.frame, self.original_checksum, self.resimulated_checksum
+        )?;
+
+        for difference in &self.differences {
+            write!(f, "\n  {difference}")?;
+        }
+
+        for mismatch in &self.checksum_mismatches {
+            write!(f, "\n  {mismatch}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`Resource`] holding the original and most recently resimulated state of recent frames.
+#[derive(Resource)]
+pub struct SyncTestHistory {
+    frames: HashMap<i32, (SyncTestFrame, Option<SyncTestFrame>)>,
+    depth: usize,
+}
+
+impl Default for SyncTestHistory {
+    fn default() -> Self {
+        Self {
+            frames: default(),
+            depth: DEFAULT_FPS,
+        }
+    }
+}
+
+impl SyncTestHistory {
+    pub fn set_depth(&mut self, depth: usize) -> &mut Self {
+        self.depth = depth;
+        self
+    }
+
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn record(&mut self, frame: i32, state: SyncTestFrame) {
+        match self.frames.get_mut(&frame) {
+            Some((_, resimulated)) => *resimulated = Some(state),
+            None => {
+                self.frames.insert(frame, (state, None));
+            }
+        }
+
+        let oldest = frame.saturating_sub(self.depth as i32);
+        self.frames.retain(|&recorded, _| recorded >= oldest);
+    }
+
+    /// Compare the original and resimulated state of every recorded frame, removing and returning
+    /// all frames which differ.
+    fn take_mismatches(&mut self) -> Vec<SyncTestMismatch> {
+        let mut mismatches = Vec::new();
+        let mut reported = HashSet::new();
+
+        for (&frame, (original, resimulated)) in &self.frames {
+            let Some(resimulated) = resimulated else {
+                continue;
+            };
+
+            let differences = original.diff(resimulated);
+            let checksum_mismatches = match (&original.report, &resimulated.report) {
+                (Some(original), Some(resimulated)) => original.diff(resimulated),
+                _ => Vec::new(),
+            };
+
+            if original.checksum == resimulated.checksum
+                && differences.is_empty()
+                && checksum_mismatches.is_empty()
+            {
+                continue;
+            }
+
+            reported.insert(frame);
+            mismatches.push(SyncTestMismatch {
+                frame,
+                original_checksum: original.checksum,
+                resimulated_checksum: resimulated.checksum,
+                differences,
+                checksum_mismatches,
+            });
+        }
+
+        self.frames.retain(|frame, _| !reported.contains(frame));
+        mismatches.sort_by_key(|mismatch| mismatch.frame);
+
+        mismatches
+    }
+}
+
+/// Report all mismatched frames recorded in the [`SyncTestHistory`], if present.
+pub(crate) fn report_sync_test_mismatches(world: &mut World) {
+    let Some(mut history) = world.get_resource_mut::<SyncTestHistory>() else {
+        return;
+    };
+
+    let mismatches = history.take_mismatches();
+
+    if mismatches.is_empty() {
+        warn!("SyncTest mismatch detected, but no differences were recorded");
+    }
+
+    let mut events = world.resource_mut::<Events<SyncTestMismatch>>();
+
+    for mismatch in mismatches {
+        warn!("{mismatch}");
+        events.send(mismatch);
+    }
+}
+
+/// A [`Plugin`] which records the original and resimulated snapshots of every frame during a
+/// [`SyncTestSession`](`ggrs::SyncTestSession`). When a checksum mismatch is detected, a
+/// [`SyncTestMismatch`] event is sent (and logged) for each offending frame, listing which
+/// [`Rollback`] entities, [`Components`](`Component`) and [`Resources`](`Resource`) differ.
+///
+/// Only snapshots which can be viewed through [`Reflect`] are compared directly. Add the
+/// [`ChecksumReportPlugin`](`crate::ChecksumReportPlugin`) as well to include a per-type
+/// breakdown of the [`Checksum`].
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, SyncTestMismatch, SyncTestReportPlugin};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// app.add_plugins(SyncTestReportPlugin);
+///
+/// fn on_mismatch(mut mismatches: EventReader<SyncTestMismatch>) {
+///     for mismatch in mismatches.iter() {
+///         error!("Nondeterminism detected:\n{mismatch}");
+///     }
+/// }
+///
+/// app.add_systems(Update, on_mismatch);
+/// # }
+/// ```
+pub struct SyncTestReportPlugin;
+
+impl SyncTestReportPlugin {
+    /// A [`System`] which records the snapshots of the current frame.
+    pub fn record(world: &mut World) {
+        let frame = world.resource::<RollbackFrameCount>().0;
+        let state = SyncTestFrame::capture(world, frame);
+
+        world.resource_mut::<SyncTestHistory>().record(frame, state);
+    }
+}
+
+impl Plugin for SyncTestReportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SyncTestHistory>()
+            .add_event::<SyncTestMismatch>()
+            .add_systems(SaveWorld, Self::record.after(SaveWorldSet::Snapshot));
+    }
+}
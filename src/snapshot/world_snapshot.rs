@@ -0,0 +1,913 @@
+use std::fmt;
+
+use bevy::{
+    ecs::{
+        entity::EntityMap,
+        reflect::{ReflectComponent, ReflectMapEntities, ReflectResource},
+        system::EntityCommand,
+    },
+    prelude::*,
+    utils::HashMap,
+};
+
+use crate::{
+    AddRollbackCommand, GgrsComponentDeltaSnapshots, GgrsComponentSnapshots, GgrsResourceSnapshots,
+    GgrsTrackedComponentSnapshots, Rollback, RollbackFrameCount, RollbackOrdered,
+    RollbackRegistration, Strategy,
+};
+
+type ComponentCapture = fn(&World, i32) -> Option<Captured<Vec<(Rollback, Box<dyn Reflect>)>>>;
+type ResourceCapture = fn(&World, i32) -> Option<Captured<Box<dyn Reflect>>>;
+
+/// A captured snapshot, or the name of its type if its [`Strategy`] cannot be reflected.
+type Captured<T> = Result<T, &'static str>;
+
+/// A [`Resource`] listing every snapshot which can be viewed through [`Reflect`], used to
+/// build a [`WorldSnapshot`]. [`ComponentSnapshotPlugin`](`crate::ComponentSnapshotPlugin`) and
+/// [`ResourceSnapshotPlugin`](`crate::ResourceSnapshotPlugin`) register their snapshots here
+/// automatically. Only snapshots using a [`Strategy`] which implements
+/// [`as_reflect`](`Strategy::as_reflect`), such as [`ReflectStrategy`](`crate::ReflectStrategy`),
+/// will contribute to a [`WorldSnapshot`].
+#[derive(Resource, Default)]
+pub struct SnapshotReflectRegistry {
+    components: Vec<ComponentCapture>,
+    resources: Vec<ResourceCapture>,
+}
+
+/// Every reflected snapshot stored for a single frame, collected by [`SnapshotReflectRegistry`].
+pub(crate) struct CapturedSnapshots {
+    pub(crate) components: HashMap<Rollback, Vec<Box<dyn Reflect>>>,
+    pub(crate) resources: Vec<Box<dyn Reflect>>,
+    /// Names of the types which have a snapshot for this frame which cannot be reflected.
+    pub(crate) omitted: Vec<&'static str>,
+}
+
+impl SnapshotReflectRegistry {
+    /// Register the [`Component`] snapshots for the [`Strategy`] `S`.
+    pub fn register_component<S>(&mut self) -> &mut Self
+    where
+        S: Strategy,
+        S::Target: Component,
+        S::Stored: Send + Sync + 'static,
+    {
+        self.components.push(capture_components::<S>);
+        self
+    }
+
+    /// Register the delta [`Component`] snapshots for the [`Strategy`] `S`.
+    pub fn register_delta_component<S>(&mut self) -> &mut Self
+    where
+        S: Strategy,
+        S::Target: Component,
+        S::Stored: Send + Sync + 'static,
+    {
+        self.components.push(capture_delta_components::<S>);
+        self
+    }
+
+    /// Register the change-tracked [`Component`] snapshots for the [`Strategy`] `S`.
+    pub fn register_tracked_component<S>(&mut self) -> &mut Self
+    where
+        S: Strategy,
+        S::Target: Component,
+        S::Stored: Send + Sync + 'static,
+    {
+        self.components.push(capture_tracked_components::<S>);
+        self
+    }
+
+    /// Register the [`Resource`] snapshots for the [`Strategy`] `S`.
+    pub fn register_resource<S>(&mut self) -> &mut Self
+    where
+        S: Strategy,
+        S::Target: Resource,
+        S::Stored: Send + Sync + 'static,
+    {
+        self.resources.push(capture_resource::<S>);
+        self
+    }
+
+    /// Collect all reflected [`Component`] snapshots by [`Rollback`], and all reflected
+    /// [`Resource`] snapshots, for the provided frame.
+    pub(crate) fn capture(&self, world: &World, frame: i32) -> CapturedSnapshots {
+        let mut captured = CapturedSnapshots {
+            components: HashMap::new(),
+            resources: Vec::new(),
+            omitted: Vec::new(),
+        };
+
+        for capture in &self.components {
+            match capture(world, frame) {
+                Some(Ok(components)) => {
+                    for (rollback, component) in components {
+                        captured
+                            .components
+                            .entry(rollback)
+                            .or_default()
+                            .push(component);
+                    }
+                }
+                Some(Err(name)) => captured.omitted.push(name),
+                None => {}
+            }
+        }
+
+        for capture in &self.resources {
+            match capture(world, frame) {
+                Some(Ok(resource)) => captured.resources.push(resource),
+                Some(Err(name)) => captured.omitted.push(name),
+                None => {}
+            }
+        }
+
+        captured
+    }
+}
+
+fn reflect_all<'a, S>(
+    stored: impl IntoIterator<Item = (Rollback, &'a S::Stored)>,
+) -> Captured<Vec<(Rollback, Box<dyn Reflect>)>>
+where
+    S: Strategy,
+    S::Stored: 'a,
+{
+    stored
+        .into_iter()
+        .map(|(rollback, stored)| {
+            S::as_reflect(stored).map(|value| (rollback, value.clone_value()))
+        })
+        .collect::<Option<_>>()
+        .ok_or(std::any::type_name::<S::Target>())
+}
+
+fn capture_components<S>(
+    world: &World,
+    frame: i32,
+) -> Option<Captured<Vec<(Rollback, Box<dyn Reflect>)>>>
+where
+    S: Strategy,
+    S::Target: Component,
+    S::Stored: Send + Sync + 'static,
+{
+    let snapshot = world
+        .get_resource::<GgrsComponentSnapshots<S::Target, S::Stored>>()?
+        .peek(frame)?;
+
+    Some(reflect_all::<S>(
+        snapshot
+            .iter()
+            .map(|(&rollback, stored)| (rollback, stored)),
+    ))
+}
+
+fn capture_delta_components<S>(
+    world: &World,
+    frame: i32,
+) -> Option<Captured<Vec<(Rollback, Box<dyn Reflect>)>>>
+where
+    S: Strategy,
+    S::Target: Component,
+    S::Stored: Send + Sync + 'static,
+{
+    let snapshot = world
+        .get_resource::<GgrsComponentDeltaSnapshots<S::Target, S::Stored>>()?
+        .peek(frame)?;
+
+    Some(reflect_all::<S>(snapshot))
+}
+
+fn capture_tracked_components<S>(
+    world: &World,
+    frame: i32,
+) -> Option<Captured<Vec<(Rollback, Box<dyn Reflect>)>>>
+where
+    S: Strategy,
+    S::Target: Component,
+    S::Stored: Send + Sync + 'static,
+{
+    let snapshot = world
+        .get_resource::<GgrsTrackedComponentSnapshots<S::Target, S::Stored>>()?
+        .peek(frame)?;
+
+    Some(reflect_all::<S>(
+        snapshot
+            .iter()
+            .map(|(&rollback, stored)| (rollback, &**stored)),
+    ))
+}
+
+fn capture_resource<S>(world: &World, frame: i32) -> Option<Captured<Box<dyn Reflect>>>
+where
+    S: Strategy,
+    S::Target: Resource,
+    S::Stored: Send + Sync + 'static,
+{
+    let stored = world
+        .get_resource::<GgrsResourceSnapshots<S::Target, S::Stored>>()?
+        .peek(frame)?
+        .as_ref()?;
+
+    Some(
+        S::as_reflect(stored)
+            .map(|value| value.clone_value())
+            .ok_or(std::any::type_name::<S::Target>()),
+    )
+}
+
+/// All reflected [`Components`](`Component`) of a single [`Rollback`] [`Entity`] within a [`WorldSnapshot`].
+#[derive(Debug)]
+pub struct EntitySnapshot {
+    /// The [`Entity`] as it was when the snapshot was taken.
+    pub entity: Entity,
+    /// How the [`Rollback`] was registered in [`RollbackOrdered`], or [`None`] if it had
+    /// already been pruned when the snapshot was taken.
+    pub registration: Option<RollbackRegistration>,
+    pub components: Vec<Box<dyn Reflect>>,
+}
+
+impl Clone for EntitySnapshot {
+    fn clone(&self) -> Self {
+        Self {
+            entity: self.entity,
+            registration: self.registration,
+            components: self.components.iter().map(|c| c.clone_value()).collect(),
+        }
+    }
+}
+
+/// Every [`Rollback`] [`Entity`], and every [`Component`] and [`Resource`] snapshot available
+/// through [`Reflect`], for a single frame.
+///
+/// A [`WorldSnapshot`] can be written into a fresh [`World`], and with the `serialize` feature,
+/// saved to and loaded from a file. This enables save states, crash dumps of the exact state
+/// at a desync, and bootstrapping peers which join late.
+///
+/// NOTE: Only types snapshot with a [`Strategy`] which can be reflected, such as those registered
+/// with [`rollback_component_with_reflect`](`crate::GgrsApp::rollback_component_with_reflect`),
+/// are included. Types using [`CopyStrategy`](`crate::CopyStrategy`) or
+/// [`CloneStrategy`](`crate::CloneStrategy`) are listed in
+/// [`omitted`](`WorldSnapshot::omitted`) instead, and such an incomplete snapshot cannot be
+/// written into a [`World`].
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, WorldSnapshot, RollbackFrameCount};
+/// #
+/// # fn dump(world: &World) {
+/// let frame = (*world.resource::<RollbackFrameCount>()).into();
+///
+/// if let Some(snapshot) = WorldSnapshot::from_snapshots(world, frame) {
+///     info!("Frame {frame} has {} rollback entities", snapshot.entities.len());
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WorldSnapshot {
+    /// The frame this snapshot was taken from.
+    pub frame: i32,
+    /// All [`Rollback`] entities, sorted by [`Entity`].
+    pub entities: Vec<EntitySnapshot>,
+    pub resources: Vec<Box<dyn Reflect>>,
+    /// The [`RollbackOrdered::next_index`] when the snapshot was taken.
+    pub next_index: usize,
+    /// Names of the types which had a snapshot for this frame, but were left out because their
+    /// [`Strategy`] cannot be reflected.
+    pub omitted: Vec<String>,
+}
+
+impl Clone for WorldSnapshot {
+    fn clone(&self) -> Self {
+        Self {
+            frame: self.frame,
+            entities: self.entities.clone(),
+            resources: self.resources.iter().map(|r| r.clone_value()).collect(),
+            next_index: self.next_index,
+            omitted: self.omitted.clone(),
+        }
+    }
+}
+
+impl WorldSnapshot {
+    /// Assemble a [`WorldSnapshot`] from the snapshots stored for the provided frame. Returns [`None`]
+    /// if no [`Entity`] snapshot exists for that frame.
+    pub fn from_snapshots(world: &World, frame: i32) -> Option<Self> {
+        let entities = world
+            .get_resource::<GgrsComponentSnapshots<Entity>>()?
+            .peek(frame)?;
+
+        let rollback_ordered = world.get_resource::<RollbackOrdered>();
+
+        let mut entities = entities
+            .iter()
+            .map(|(&rollback, &entity)| {
+                (
+                    rollback,
+                    EntitySnapshot {
+                        entity,
+                        registration: rollback_ordered
+                            .and_then(|ordered| ordered.registration(rollback)),
+                        components: Vec::new(),
+                    },
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut resources = Vec::new();
+        let mut omitted = Vec::new();
+
+        if let Some(registry) = world.get_resource::<SnapshotReflectRegistry>() {
+            let captured = registry.capture(world, frame);
+
+            for (rollback, components) in captured.components {
+                if let Some(entity) = entities.get_mut(&rollback) {
+                    entity.components = components;
+                }
+            }
+
+            resources = captured.resources;
+
+            if !captured.omitted.is_empty() {
+                warn!(
+                    "Snapshot for frame {frame} omits {:?}, as their snapshots cannot be reflected",
+                    captured.omitted
+                );
+            }
+
+            omitted = captured.omitted.into_iter().map(str::to_owned).collect();
+        }
+
+        let mut entities = entities.into_values().collect::<Vec<_>>();
+        entities.sort_by_key(|entity| entity.entity);
+
+        Some(Self {
+            frame,
+            entities,
+            resources,
+            next_index: rollback_ordered.map_or(0, RollbackOrdered::next_index),
+            omitted,
+        })
+    }
+
+    /// Write the contents of this snapshot into a [`World`], spawning a new [`Rollback`] [`Entity`]
+    /// for every [`EntitySnapshot`] and inserting every [`Resource`]. Entity references are
+    /// updated for all types registered with [`ReflectMapEntities`], and the [`RollbackFrameCount`]
+    /// is set to the frame of the snapshot.
+    ///
+    /// Spawned entities keep their [`RollbackRegistration`], so [`RollbackOrdered::id`] matches
+    /// the [`World`] the snapshot was taken from.
+    ///
+    /// The [`World`] must contain an [`AppTypeRegistry`], in which every stored type is registered
+    /// with either [`ReflectComponent`] or [`ReflectResource`], and the snapshot must not have
+    /// [`omitted`](`WorldSnapshot::omitted`) any type. This is checked before the [`World`] is
+    /// modified, so the [`World`] is left untouched if an error is returned.
+    ///
+    /// Returns the mapping from the [`Entities`](`Entity`) in the snapshot to the spawned ones.
+    pub fn write_to_world(&self, world: &mut World) -> Result<EntityMap, WorldSnapshotError> {
+        if !self.omitted.is_empty() {
+            return Err(WorldSnapshotError::Incomplete(self.omitted.clone()));
+        }
+
+        let registry = world
+            .get_resource::<AppTypeRegistry>()
+            .ok_or(WorldSnapshotError::MissingTypeRegistry)?
+            .clone();
+        let registry = registry.read();
+
+        let components = self
+            .entities
+            .iter()
+            .map(|snapshot| {
+                snapshot
+                    .components
+                    .iter()
+                    .map(|component| {
+                        registry
+                            .get_with_name(component.type_name())
+                            .and_then(|registration| registration.data::<ReflectComponent>())
+                            .map(|reflect_component| (reflect_component, component.as_ref()))
+                            .ok_or_else(|| {
+                                WorldSnapshotError::UnregisteredComponent(
+                                    component.type_name().to_owned(),
+                                )
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let resources = self
+            .resources
+            .iter()
+            .map(|resource| {
+                registry
+                    .get_with_name(resource.type_name())
+                    .and_then(|registration| registration.data::<ReflectResource>())
+                    .map(|reflect_resource| (reflect_resource, resource.as_ref()))
+                    .ok_or_else(|| {
+                        WorldSnapshotError::UnregisteredResource(resource.type_name().to_owned())
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut entity_map = EntityMap::default();
+
+        // Entities without a registration must not reuse an index from the original World
+        world
+            .get_resource_or_insert_with::<RollbackOrdered>(default)
+            .reserve_index(self.next_index);
+
+        for (snapshot, components) in self.entities.iter().zip(components) {
+            let entity = world.spawn_empty().id();
+
+            match snapshot.registration {
+                Some(registration) => {
+                    let rollback = Rollback::new(entity);
+
+                    world.entity_mut(entity).insert(rollback);
+                    world
+                        .get_resource_or_insert_with::<RollbackOrdered>(default)
+                        .restore(rollback, registration);
+                }
+                None => AddRollbackCommand.apply(entity, world),
+            }
+
+            entity_map.insert(snapshot.entity, entity);
+
+            for (reflect_component, component) in components {
+                reflect_component.insert(&mut world.entity_mut(entity), component);
+            }
+        }
+
+        for (reflect_resource, resource) in resources {
+            reflect_resource.insert(world, resource);
+        }
+
+        let spawned = entity_map.values().collect::<Vec<_>>();
+
+        for registration in registry.iter() {
+            if let Some(map_entities) = registration.data::<ReflectMapEntities>() {
+                map_entities.map_entities(world, &mut entity_map, &spawned);
+            }
+        }
+
+        world.insert_resource(RollbackFrameCount(self.frame));
+
+        trace!(
+            "Wrote snapshot for frame {} with {} entity(s)",
+            self.frame,
+            self.entities.len()
+        );
+
+        Ok(entity_map)
+    }
+}
+
+/// Errors which can occur while writing or (de)serializing a [`WorldSnapshot`].
+#[derive(Debug)]
+pub enum WorldSnapshotError {
+    /// The [`World`] does not contain an [`AppTypeRegistry`].
+    MissingTypeRegistry,
+    /// The snapshot omits the named types, as their snapshots cannot be reflected.
+    Incomplete(Vec<String>),
+    /// The named type is not registered with [`ReflectComponent`].
+    UnregisteredComponent(String),
+    /// The named type is not registered with [`ReflectResource`].
+    UnregisteredResource(String),
+    /// The snapshot could not be (de)serialized.
+    Serialization(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for WorldSnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldSnapshotError::MissingTypeRegistry => {
+                write!(f, "the world does not contain an AppTypeRegistry")
+            }
+            WorldSnapshotError::Incomplete(names) => {
+                write!(
+                    f,
+                    "the snapshot omits {names:?}, as they cannot be reflected"
+                )
+            }
+            WorldSnapshotError::UnregisteredComponent(name) => {
+                write!(f, "{name} is not registered with ReflectComponent")
+            }
+            WorldSnapshotError::UnregisteredResource(name) => {
+                write!(f, "{name} is not registered with ReflectResource")
+            }
+            WorldSnapshotError::Serialization(error) => write!(f, "{error}"),
+            WorldSnapshotError::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for WorldSnapshotError {}
+
+impl From<std::io::Error> for WorldSnapshotError {
+    fn from(error: std::io::Error) -> Self {
+        WorldSnapshotError::Io(error)
+    }
+}
+
+#[cfg(feature = "serialize")]
+mod serialize {
+    use super::{EntitySnapshot, WorldSnapshot, WorldSnapshotError};
+    use crate::RollbackRegistration;
+    use bevy::{
+        prelude::*,
+        reflect::{
+            serde::{ReflectSerializer, UntypedReflectDeserializer},
+            TypeRegistry,
+        },
+    };
+    use serde::{
+        de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+        ser::{SerializeSeq, SerializeStruct},
+        Deserializer, Serialize, Serializer,
+    };
+    use std::{fmt, path::Path};
+
+    const WORLD_SNAPSHOT_FIELDS: &[&str] =
+        &["frame", "entities", "resources", "next_index", "omitted"];
+    const ENTITY_SNAPSHOT_FIELDS: &[&str] = &["entity", "registration", "components"];
+
+    fn registration_to_tuple(registration: Option<RollbackRegistration>) -> Option<(usize, u64)> {
+        registration.map(|registration| (registration.index, registration.spawn_id))
+    }
+
+    fn registration_from_tuple(registration: Option<(usize, u64)>) -> Option<RollbackRegistration> {
+        registration.map(|(index, spawn_id)| RollbackRegistration { index, spawn_id })
+    }
+
+    /// Serializes a [`WorldSnapshot`] using the provided [`TypeRegistry`].
+    pub struct WorldSnapshotSerializer<'a> {
+        pub snapshot: &'a WorldSnapshot,
+        pub registry: &'a TypeRegistry,
+    }
+
+    impl<'a> WorldSnapshotSerializer<'a> {
+        pub fn new(snapshot: &'a WorldSnapshot, registry: &'a TypeRegistry) -> Self {
+            Self { snapshot, registry }
+        }
+    }
+
+    impl<'a> Serialize for WorldSnapshotSerializer<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("WorldSnapshot", 5)?;
+            state.serialize_field("frame", &self.snapshot.frame)?;
+            state.serialize_field(
+                "entities",
+                &EntitiesSerializer {
+                    entities: &self.snapshot.entities,
+                    registry: self.registry,
+                },
+            )?;
+            state.serialize_field(
+                "resources",
+                &ReflectListSerializer {
+                    values: &self.snapshot.resources,
+                    registry: self.registry,
+                },
+            )?;
+            state.serialize_field("next_index", &self.snapshot.next_index)?;
+            state.serialize_field("omitted", &self.snapshot.omitted)?;
+            state.end()
+        }
+    }
+
+    struct EntitiesSerializer<'a> {
+        entities: &'a [EntitySnapshot],
+        registry: &'a TypeRegistry,
+    }
+
+    impl<'a> Serialize for EntitiesSerializer<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_seq(Some(self.entities.len()))?;
+            for entity in self.entities {
+                state.serialize_element(&EntitySerializer {
+                    entity,
+                    registry: self.registry,
+                })?;
+            }
+            state.end()
+        }
+    }
+
+    struct EntitySerializer<'a> {
+        entity: &'a EntitySnapshot,
+        registry: &'a TypeRegistry,
+    }
+
+    impl<'a> Serialize for EntitySerializer<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("EntitySnapshot", 3)?;
+            state.serialize_field("entity", &self.entity.entity.to_bits())?;
+            state.serialize_field(
+                "registration",
+                &registration_to_tuple(self.entity.registration),
+            )?;
+            state.serialize_field(
+                "components",
+                &ReflectListSerializer {
+                    values: &self.entity.components,
+                    registry: self.registry,
+                },
+            )?;
+            state.end()
+        }
+    }
+
+    struct ReflectListSerializer<'a> {
+        values: &'a [Box<dyn Reflect>],
+        registry: &'a TypeRegistry,
+    }
+
+    impl<'a> Serialize for ReflectListSerializer<'a> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_seq(Some(self.values.len()))?;
+            for value in self.values {
+                state.serialize_element(&ReflectSerializer::new(value.as_ref(), self.registry))?;
+            }
+            state.end()
+        }
+    }
+
+    /// Deserializes a [`WorldSnapshot`] using the provided [`TypeRegistry`].
+    pub struct WorldSnapshotDeserializer<'a> {
+        pub registry: &'a TypeRegistry,
+    }
+
+    impl<'a> WorldSnapshotDeserializer<'a> {
+        pub fn new(registry: &'a TypeRegistry) -> Self {
+            Self { registry }
+        }
+    }
+
+    impl<'a, 'de> DeserializeSeed<'de> for WorldSnapshotDeserializer<'a> {
+        type Value = WorldSnapshot;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_struct("WorldSnapshot", WORLD_SNAPSHOT_FIELDS, self)
+        }
+    }
+
+    impl<'a, 'de> Visitor<'de> for WorldSnapshotDeserializer<'a> {
+        type Value = WorldSnapshot;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a world snapshot")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let frame = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let entities = seq
+                .next_element_seed(EntitiesDeserializer {
+                    registry: self.registry,
+                })?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            let resources = seq
+                .next_element_seed(ReflectListDeserializer {
+                    registry: self.registry,
+                })?
+                .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+            let next_index = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(3, &self))?;
+            let omitted = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(4, &self))?;
+
+            Ok(WorldSnapshot {
+                frame,
+                entities,
+                resources,
+                next_index,
+                omitted,
+            })
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut frame = None;
+            let mut entities = None;
+            let mut resources = None;
+            let mut next_index = None;
+            let mut omitted = None;
+
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    "frame" => frame = Some(map.next_value()?),
+                    "entities" => {
+                        entities = Some(map.next_value_seed(EntitiesDeserializer {
+                            registry: self.registry,
+                        })?)
+                    }
+                    "resources" => {
+                        resources = Some(map.next_value_seed(ReflectListDeserializer {
+                            registry: self.registry,
+                        })?)
+                    }
+                    "next_index" => next_index = Some(map.next_value()?),
+                    "omitted" => omitted = Some(map.next_value()?),
+                    other => return Err(de::Error::unknown_field(other, WORLD_SNAPSHOT_FIELDS)),
+                }
+            }
+
+            Ok(WorldSnapshot {
+                frame: frame.ok_or_else(|| de::Error::missing_field("frame"))?,
+                entities: entities.ok_or_else(|| de::Error::missing_field("entities"))?,
+                resources: resources.ok_or_else(|| de::Error::missing_field("resources"))?,
+                next_index: next_index.ok_or_else(|| de::Error::missing_field("next_index"))?,
+                omitted: omitted.ok_or_else(|| de::Error::missing_field("omitted"))?,
+            })
+        }
+    }
+
+    struct EntitiesDeserializer<'a> {
+        registry: &'a TypeRegistry,
+    }
+
+    impl<'a, 'de> DeserializeSeed<'de> for EntitiesDeserializer<'a> {
+        type Value = Vec<EntitySnapshot>;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_seq(self)
+        }
+    }
+
+    impl<'a, 'de> Visitor<'de> for EntitiesDeserializer<'a> {
+        type Value = Vec<EntitySnapshot>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a list of entity snapshots")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut entities = Vec::new();
+            while let Some(entity) = seq.next_element_seed(EntityDeserializer {
+                registry: self.registry,
+            })? {
+                entities.push(entity);
+            }
+            Ok(entities)
+        }
+    }
+
+    struct EntityDeserializer<'a> {
+        registry: &'a TypeRegistry,
+    }
+
+    impl<'a, 'de> DeserializeSeed<'de> for EntityDeserializer<'a> {
+        type Value = EntitySnapshot;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_struct("EntitySnapshot", ENTITY_SNAPSHOT_FIELDS, self)
+        }
+    }
+
+    impl<'a, 'de> Visitor<'de> for EntityDeserializer<'a> {
+        type Value = EntitySnapshot;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an entity snapshot")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let entity = seq
+                .next_element::<u64>()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let registration = seq
+                .next_element::<Option<(usize, u64)>>()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            let components = seq
+                .next_element_seed(ReflectListDeserializer {
+                    registry: self.registry,
+                })?
+                .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+
+            Ok(EntitySnapshot {
+                entity: Entity::from_bits(entity),
+                registration: registration_from_tuple(registration),
+                components,
+            })
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entity = None;
+            let mut registration = None;
+            let mut components = None;
+
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    "entity" => entity = Some(map.next_value::<u64>()?),
+                    "registration" => {
+                        registration = Some(map.next_value::<Option<(usize, u64)>>()?)
+                    }
+                    "components" => {
+                        components = Some(map.next_value_seed(ReflectListDeserializer {
+                            registry: self.registry,
+                        })?)
+                    }
+                    other => return Err(de::Error::unknown_field(other, ENTITY_SNAPSHOT_FIELDS)),
+                }
+            }
+
+            Ok(EntitySnapshot {
+                entity: Entity::from_bits(
+                    entity.ok_or_else(|| de::Error::missing_field("entity"))?,
+                ),
+                registration: registration_from_tuple(
+                    registration.ok_or_else(|| de::Error::missing_field("registration"))?,
+                ),
+                components: components.ok_or_else(|| de::Error::missing_field("components"))?,
+            })
+        }
+    }
+
+    struct ReflectListDeserializer<'a> {
+        registry: &'a TypeRegistry,
+    }
+
+    impl<'a, 'de> DeserializeSeed<'de> for ReflectListDeserializer<'a> {
+        type Value = Vec<Box<dyn Reflect>>;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_seq(self)
+        }
+    }
+
+    impl<'a, 'de> Visitor<'de> for ReflectListDeserializer<'a> {
+        type Value = Vec<Box<dyn Reflect>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a list of reflected values")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut values = Vec::new();
+            while let Some(value) =
+                seq.next_element_seed(UntypedReflectDeserializer::new(self.registry))?
+            {
+                values.push(value);
+            }
+            Ok(values)
+        }
+    }
+
+    impl WorldSnapshot {
+        /// Serialize this snapshot into a [RON](https://github.com/ron-rs/ron) string.
+        pub fn to_ron(&self, registry: &TypeRegistry) -> Result<String, WorldSnapshotError> {
+            ron::ser::to_string_pretty(
+                &WorldSnapshotSerializer::new(self, registry),
+                ron::ser::PrettyConfig::default(),
+            )
+            .map_err(|error| WorldSnapshotError::Serialization(error.to_string()))
+        }
+
+        /// Deserialize a snapshot from a [RON](https://github.com/ron-rs/ron) string.
+        pub fn from_ron(ron: &str, registry: &TypeRegistry) -> Result<Self, WorldSnapshotError> {
+            let mut deserializer = ron::Deserializer::from_str(ron)
+                .map_err(|error| WorldSnapshotError::Serialization(error.to_string()))?;
+
+            WorldSnapshotDeserializer::new(registry)
+                .deserialize(&mut deserializer)
+                .map_err(|error| WorldSnapshotError::Serialization(error.to_string()))
+        }
+
+        /// Save this snapshot to a file as [RON](https://github.com/ron-rs/ron).
+        pub fn save(
+            &self,
+            path: impl AsRef<Path>,
+            registry: &TypeRegistry,
+        ) -> Result<(), WorldSnapshotError> {
+            std::fs::write(path, self.to_ron(registry)?)?;
+            Ok(())
+        }
+
+        /// Load a snapshot previously written with [`save`](`WorldSnapshot::save`).
+        pub fn load(
+            path: impl AsRef<Path>,
+            registry: &TypeRegistry,
+        ) -> Result<Self, WorldSnapshotError> {
+            Self::from_ron(&std::fs::read_to_string(path)?, registry)
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+pub use serialize::*;
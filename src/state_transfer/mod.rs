@@ -0,0 +1,281 @@
+use crate::{
+    ConfirmedFrameCount, GgrsComponentSnapshots, HighestSimulatedFrame, LoadWorld,
+    RollbackFrameCount, SaveWorld, SessionStartFrame, WorldSnapshot, WorldSnapshotError,
+};
+use bevy::{ecs::entity::EntityMap, prelude::*};
+use std::fmt;
+
+#[cfg(feature = "serialize")]
+mod protocol;
+
+#[cfg(feature = "serialize")]
+pub use protocol::*;
+
+/// The default maximum payload size of a [`StateTransferChunk`], chosen to comfortably fit
+/// within a single UDP datagram.
+pub const STATE_TRANSFER_CHUNK_SIZE: usize = 1024;
+
+const CHUNK_HEADER_SIZE: usize = 8;
+
+/// Take a [`WorldSnapshot`] of the most recently confirmed frame, to be sent to a peer which is
+/// joining (or rejoining) the game. Returns [`None`] if no frame has been confirmed yet, or the
+/// snapshot for that frame has already been discarded.
+///
+/// GGRS sessions cannot be joined once they have started. Instead, all peers must agree on
+/// a frame to resume from, after which:
+/// - Existing peers remove their [`Session`](`crate::Session`) and call [`rewind_to_frame`].
+/// - The joining peer receives the snapshot and calls [`import_state`].
+/// - All peers start and insert a new [`Session`](`crate::Session`), which will continue from
+///   the [`SessionStartFrame`].
+///
+/// With the `serialize` feature, the `StateTransferPlugin` runs this protocol between a host and
+/// a joining peer over a socket.
+///
+/// Only snapshots which can be viewed through [`Reflect`] are included, see
+/// [`SnapshotReflectRegistry`](`crate::SnapshotReflectRegistry`). The
+/// [`RollbackOrdered`](`crate::RollbackOrdered`) registration of every entity is included as
+/// well, so checksums continue to match once the state is imported.
+pub fn export_confirmed_state(world: &World) -> Option<WorldSnapshot> {
+    let frame = world.get_resource::<ConfirmedFrameCount>()?.0;
+
+    if frame < 0 {
+        return None;
+    }
+
+    WorldSnapshot::from_snapshots(world, frame)
+}
+
+/// Restore the snapshot taken for the provided frame, and set it as the [`SessionStartFrame`]
+/// for the next [`Session`](`crate::Session`). This is the counterpart of [`import_state`] for
+/// peers which already have the world at that frame in their snapshots.
+///
+/// Returns [`StateTransferError::MissingSnapshot`] without modifying the [`World`] if no snapshot
+/// is stored for the provided frame.
+pub fn rewind_to_frame(world: &mut World, frame: i32) -> Result<(), StateTransferError> {
+    let stored = world
+        .get_resource::<GgrsComponentSnapshots<Entity>>()
+        .and_then(|snapshots| snapshots.peek(frame))
+        .is_some();
+
+    if !stored {
+        return Err(StateTransferError::MissingSnapshot(frame));
+    }
+
+    world.insert_resource(RollbackFrameCount(frame));
+    world.run_schedule(LoadWorld);
+
+    start_from_frame(world, frame);
+
+    Ok(())
+}
+
+/// Write a [`WorldSnapshot`] received from another peer into this [`World`], seed the
+/// [`GgrsSnapshots`](`crate::GgrsSnapshots`) with it, and set its frame as the
+/// [`SessionStartFrame`] for the next [`Session`](`crate::Session`).
+///
+/// This should be called on a [`World`] without any [`Rollback`](`crate::Rollback`) entities.
+pub fn import_state(
+    world: &mut World,
+    snapshot: &WorldSnapshot,
+) -> Result<EntityMap, WorldSnapshotError> {
+    let entity_map = snapshot.write_to_world(world)?;
+
+    start_from_frame(world, snapshot.frame);
+    world.run_schedule(SaveWorld);
+
+    Ok(entity_map)
+}
+
+fn start_from_frame(world: &mut World, frame: i32) {
+    world.insert_resource(SessionStartFrame(frame));
+    world.insert_resource(RollbackFrameCount(frame));
+
+    // Matches the ConfirmedFrameCount used while waiting for the next session to start, as it
+    // has yet to confirm any frames of its own
+    world.insert_resource(ConfirmedFrameCount(frame - 1));
+
+    // The next session starts a new timeline, so its frames are simulated for the first time
+    world.insert_resource(HighestSimulatedFrame(frame));
+
+    debug!("next session will start from frame {frame}");
+}
+
+/// Errors which can occur while transferring the state of a game to a joining peer.
+#[derive(Debug)]
+pub enum StateTransferError {
+    /// No snapshot is stored for the frame, as it has not been simulated yet or has already been
+    /// discarded.
+    MissingSnapshot(i32),
+    /// The snapshot could not be written or (de)serialized.
+    Snapshot(WorldSnapshotError),
+    /// The [`Session`](`crate::Session`) continuing from the transferred state could not be
+    /// started.
+    Session(Box<dyn std::error::Error + Send + Sync>),
+    /// The other peer stopped responding.
+    TimedOut,
+}
+
+impl fmt::Display for StateTransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateTransferError::MissingSnapshot(frame) => {
+                write!(f, "no snapshot is stored for frame {frame}")
+            }
+            StateTransferError::Snapshot(error) => write!(f, "{error}"),
+            StateTransferError::Session(error) => write!(f, "{error}"),
+            StateTransferError::TimedOut => write!(f, "the other peer stopped responding"),
+        }
+    }
+}
+
+impl std::error::Error for StateTransferError {}
+
+impl From<WorldSnapshotError> for StateTransferError {
+    fn from(error: WorldSnapshotError) -> Self {
+        StateTransferError::Snapshot(error)
+    }
+}
+
+/// A fragment of a serialized state transfer, small enough to be sent as a single datagram.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateTransferChunk {
+    /// Identifies which transfer this chunk belongs to.
+    pub transfer: u32,
+    pub index: u16,
+    /// The total number of chunks in this transfer.
+    pub count: u16,
+    pub data: Vec<u8>,
+}
+
+impl StateTransferChunk {
+    /// Split the provided bytes into chunks with at most `chunk_size` bytes of payload.
+    ///
+    /// # Panics
+    /// If more than [`u16::MAX`] chunks would be required.
+    pub fn split(transfer: u32, bytes: &[u8], chunk_size: usize) -> Vec<Self> {
+        let chunks = bytes.chunks(chunk_size.max(1)).collect::<Vec<_>>();
+        let count = u16::try_from(chunks.len().max(1)).expect("Too many chunks for one transfer");
+
+        if chunks.is_empty() {
+            return vec![Self {
+                transfer,
+                index: 0,
+                count,
+                data: Vec::new(),
+            }];
+        }
+
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, data)| Self {
+                transfer,
+                index: index as u16,
+                count,
+                data: data.to_vec(),
+            })
+            .collect()
+    }
+
+    /// Encode this chunk into bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(CHUNK_HEADER_SIZE + self.data.len());
+        bytes.extend_from_slice(&self.transfer.to_le_bytes());
+        bytes.extend_from_slice(&self.index.to_le_bytes());
+        bytes.extend_from_slice(&self.count.to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Decode a chunk previously encoded with [`to_bytes`](`StateTransferChunk::to_bytes`).
+    /// Returns [`None`] if the bytes are not a valid chunk.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < CHUNK_HEADER_SIZE {
+            return None;
+        }
+
+        let transfer = u32::from_le_bytes(bytes[0..4].try_into().ok()?);
+        let index = u16::from_le_bytes(bytes[4..6].try_into().ok()?);
+        let count = u16::from_le_bytes(bytes[6..8].try_into().ok()?);
+
+        if index >= count {
+            return None;
+        }
+
+        Some(Self {
+            transfer,
+            index,
+            count,
+            data: bytes[CHUNK_HEADER_SIZE..].to_vec(),
+        })
+    }
+}
+
+/// Reassembles [`StateTransferChunk`]s which may arrive out of order or more than once.
+/// Receiving a chunk of a different transfer discards any progress on the current one.
+#[derive(Debug, Default)]
+pub struct StateTransferAssembler {
+    transfer: Option<u32>,
+    chunks: Vec<Option<Vec<u8>>>,
+    received: usize,
+}
+
+impl StateTransferAssembler {
+    /// Receive a chunk, returning the complete transfer once all of its chunks have arrived.
+    pub fn receive(&mut self, chunk: StateTransferChunk) -> Option<Vec<u8>> {
+        if self.transfer != Some(chunk.transfer) || self.chunks.len() != chunk.count as usize {
+            self.transfer = Some(chunk.transfer);
+            self.chunks = vec![None; chunk.count as usize];
+            self.received = 0;
+        }
+
+        let slot = self.chunks.get_mut(chunk.index as usize)?;
+
+        if slot.is_none() {
+            *slot = Some(chunk.data);
+            self.received += 1;
+        }
+
+        if self.received < self.chunks.len() {
+            return None;
+        }
+
+        let bytes = self.chunks.drain(..).flatten().flatten().collect();
+
+        self.transfer = None;
+        self.received = 0;
+
+        Some(bytes)
+    }
+
+    /// The fraction of the current transfer which has been received, from `0.0` to `1.0`.
+    pub fn progress(&self) -> f32 {
+        if self.chunks.is_empty() {
+            return 0.;
+        }
+
+        self.received as f32 / self.chunks.len() as f32
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl WorldSnapshot {
+    /// Encode this snapshot into bytes suitable for [`StateTransferChunk::split`].
+    pub fn to_transfer_bytes(
+        &self,
+        registry: &bevy::reflect::TypeRegistry,
+    ) -> Result<Vec<u8>, WorldSnapshotError> {
+        self.to_ron(registry).map(String::into_bytes)
+    }
+
+    /// Decode a snapshot reassembled by a [`StateTransferAssembler`].
+    pub fn from_transfer_bytes(
+        bytes: &[u8],
+        registry: &bevy::reflect::TypeRegistry,
+    ) -> Result<Self, WorldSnapshotError> {
+        let ron = std::str::from_utf8(bytes)
+            .map_err(|error| WorldSnapshotError::Serialization(error.to_string()))?;
+
+        Self::from_ron(ron, registry)
+    }
+}
@@ -0,0 +1,668 @@
+use super::{
+    export_confirmed_state, import_state, rewind_to_frame, StateTransferAssembler,
+    StateTransferChunk, StateTransferError, STATE_TRANSFER_CHUNK_SIZE,
+};
+use crate::{GgrsUpdateSet, Session, WorldSnapshot, WorldSnapshotError};
+use bevy::{
+    prelude::*,
+    utils::{Duration, Instant},
+};
+use ggrs::Config;
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io,
+    marker::PhantomData,
+    net::{SocketAddr, UdpSocket},
+};
+
+/// The default number of [`StateTransferChunk`]s sent by a [`StateTransferHost`] every update.
+pub const STATE_TRANSFER_CHUNKS_PER_UPDATE: usize = 64;
+
+/// The default duration a [`StateTransferHost`] waits for a joining peer to acknowledge chunks
+/// before giving up on the transfer.
+pub const STATE_TRANSFER_TIMEOUT: Duration = Duration::from_secs(5);
+
+const RECEIVE_BUFFER_SIZE: usize = 4096;
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Starts the [`Session`] continuing from the transferred state, given the address of the other
+/// peer of the transfer.
+type StartSession<C> =
+    Box<dyn FnMut(&<C as Config>::Address) -> Result<Session<C>, BoxedError> + Send + Sync>;
+
+/// Sends and receives the datagrams of a state transfer, much like a GGRS
+/// [`NonBlockingSocket`](`ggrs::NonBlockingSocket`). A separate socket from the one used by the
+/// [`Session`] is required, as the [`Session`] consumes every datagram it receives.
+pub trait StateTransferSocket<A>: Send + Sync {
+    /// Send a datagram to the provided address. Failures are not reported, as the protocol
+    /// resends anything which is not acknowledged.
+    fn send_to(&mut self, bytes: &[u8], addr: &A);
+
+    /// Receive every datagram which has arrived since the last call, without blocking.
+    fn receive_all(&mut self) -> Vec<(A, Vec<u8>)>;
+}
+
+/// A non-blocking [`UdpSocket`] implementing [`StateTransferSocket`].
+pub struct UdpStateTransferSocket {
+    socket: UdpSocket,
+    buffer: [u8; RECEIVE_BUFFER_SIZE],
+}
+
+impl UdpStateTransferSocket {
+    /// Bind a non-blocking [`UdpSocket`] to the provided port on all interfaces.
+    pub fn bind_to_port(port: u16) -> io::Result<Self> {
+        let socket = UdpSocket::bind(SocketAddr::from(([0, 0, 0, 0], port)))?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            buffer: [0; RECEIVE_BUFFER_SIZE],
+        })
+    }
+}
+
+impl StateTransferSocket<SocketAddr> for UdpStateTransferSocket {
+    fn send_to(&mut self, bytes: &[u8], addr: &SocketAddr) {
+        if let Err(error) = self.socket.send_to(bytes, addr) {
+            debug!("failed to send state transfer datagram to {addr}: {error}");
+        }
+    }
+
+    fn receive_all(&mut self) -> Vec<(SocketAddr, Vec<u8>)> {
+        let mut received = Vec::new();
+
+        loop {
+            match self.socket.recv_from(&mut self.buffer) {
+                Ok((length, addr)) => received.push((addr, self.buffer[..length].to_vec())),
+                // Some platforms report datagrams which could not be delivered earlier
+                Err(error) if error.kind() == io::ErrorKind::ConnectionReset => continue,
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+                Err(error) => {
+                    debug!("failed to receive state transfer datagram: {error}");
+                    break;
+                }
+            }
+        }
+
+        received
+    }
+}
+
+/// A datagram of the state transfer protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Message {
+    /// Sent by the joining peer until the transfer is accepted.
+    JoinRequest {
+        transfer: u32,
+    },
+    /// Sent by the host once it has taken a snapshot of the provided frame.
+    JoinAccepted {
+        transfer: u32,
+        frame: i32,
+    },
+    Chunk(StateTransferChunk),
+    /// Sent by the joining peer for every chunk it receives.
+    ChunkReceived {
+        transfer: u32,
+        index: u16,
+    },
+}
+
+impl Message {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        match self {
+            Message::JoinRequest { transfer } => {
+                bytes.push(0);
+                bytes.extend_from_slice(&transfer.to_le_bytes());
+            }
+            Message::JoinAccepted { transfer, frame } => {
+                bytes.push(1);
+                bytes.extend_from_slice(&transfer.to_le_bytes());
+                bytes.extend_from_slice(&frame.to_le_bytes());
+            }
+            Message::Chunk(chunk) => {
+                bytes.push(2);
+                bytes.extend_from_slice(&chunk.to_bytes());
+            }
+            Message::ChunkReceived { transfer, index } => {
+                bytes.push(3);
+                bytes.extend_from_slice(&transfer.to_le_bytes());
+                bytes.extend_from_slice(&index.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (&tag, body) = bytes.split_first()?;
+
+        let transfer = || Some(u32::from_le_bytes(body.get(0..4)?.try_into().ok()?));
+
+        match tag {
+            0 => Some(Message::JoinRequest {
+                transfer: transfer()?,
+            }),
+            1 => Some(Message::JoinAccepted {
+                transfer: transfer()?,
+                frame: i32::from_le_bytes(body.get(4..8)?.try_into().ok()?),
+            }),
+            2 => StateTransferChunk::from_bytes(body).map(Message::Chunk),
+            3 => Some(Message::ChunkReceived {
+                transfer: transfer()?,
+                index: u16::from_le_bytes(body.get(4..6)?.try_into().ok()?),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Sent by the [`StateTransferPlugin`] as a state transfer progresses.
+#[derive(Event, Debug)]
+pub enum StateTransferEvent {
+    /// The state at the provided frame is being transferred.
+    Started { frame: i32 },
+    /// The state at the provided frame has been transferred, and the [`Session`] continuing from
+    /// it has been inserted.
+    Completed { frame: i32 },
+    /// The transfer was abandoned.
+    Failed(StateTransferError),
+}
+
+/// A transfer in progress on a [`StateTransferHost`].
+struct HostTransfer<A> {
+    peer: A,
+    id: u32,
+    frame: i32,
+    chunks: Vec<StateTransferChunk>,
+    acknowledged: Vec<bool>,
+    /// The index of the next chunk to send.
+    cursor: usize,
+    /// When the joining peer last acknowledged a chunk, or the transfer started.
+    last_received: Instant,
+}
+
+/// A [`Resource`] which lets peers join (or rejoin) the game of this peer while it is in
+/// progress, see [`StateTransferPlugin`].
+///
+/// When a joining peer requests the state, the current [`Session`] is removed and the world is
+/// rewound to the most recently confirmed frame, which is then sent to the joining peer. Once
+/// every chunk has been acknowledged, the [`Session`] returned by `start_session` is inserted,
+/// continuing from that frame. One peer is served at a time, and requests from other peers are
+/// ignored until the current transfer has completed or timed out.
+#[derive(Resource)]
+pub struct StateTransferHost<C: Config> {
+    socket: Box<dyn StateTransferSocket<C::Address>>,
+    start_session: StartSession<C>,
+    chunk_size: usize,
+    chunks_per_update: usize,
+    timeout: Duration,
+    transfer: Option<HostTransfer<C::Address>>,
+    /// The last completed transfer, so duplicated requests for it are ignored.
+    completed: Option<(C::Address, u32)>,
+}
+
+impl<C: Config> StateTransferHost<C> {
+    /// Create a host which serves joining peers through the provided socket, and starts the
+    /// [`Session`] with the joining peer, given the address it sent its request from.
+    pub fn new<F>(socket: impl StateTransferSocket<C::Address> + 'static, start_session: F) -> Self
+    where
+        F: FnMut(&C::Address) -> Result<Session<C>, BoxedError> + Send + Sync + 'static,
+    {
+        Self {
+            socket: Box::new(socket),
+            start_session: Box::new(start_session),
+            chunk_size: STATE_TRANSFER_CHUNK_SIZE,
+            chunks_per_update: STATE_TRANSFER_CHUNKS_PER_UPDATE,
+            timeout: STATE_TRANSFER_TIMEOUT,
+            transfer: None,
+            completed: None,
+        }
+    }
+
+    /// Set the maximum payload size of every [`StateTransferChunk`]. Each chunk must fit within
+    /// a single datagram of the socket.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Set the number of chunks sent every update.
+    pub fn with_chunks_per_update(mut self, chunks_per_update: usize) -> Self {
+        self.chunks_per_update = chunks_per_update.max(1);
+        self
+    }
+
+    /// Set how long to wait for the joining peer to acknowledge a chunk before giving up.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns `true` while a state transfer is in progress.
+    pub fn is_transferring(&self) -> bool {
+        self.transfer.is_some()
+    }
+
+    fn accept(&mut self, world: &mut World, peer: C::Address, id: u32) {
+        if let Some(transfer) = self.transfer.as_ref() {
+            // The joining peer has not received the acceptance yet
+            if transfer.peer == peer && transfer.id == id {
+                let accepted = Message::JoinAccepted {
+                    transfer: id,
+                    frame: transfer.frame,
+                };
+                self.socket.send_to(&accepted.to_bytes(), &peer);
+            }
+            return;
+        }
+
+        if self.completed.as_ref() == Some(&(peer.clone(), id)) {
+            return;
+        }
+
+        // Without a confirmed frame, the joining peer keeps asking until one is available
+        let Some(snapshot) = export_confirmed_state(world) else {
+            return;
+        };
+
+        let frame = snapshot.frame;
+
+        let rewound = Self::encode(world, &snapshot)
+            .and_then(|bytes| rewind_to_frame(world, frame).map(|_| bytes));
+
+        match rewound {
+            Ok(bytes) => {
+                world.remove_resource::<Session<C>>();
+
+                let chunks = StateTransferChunk::split(id, &bytes, self.chunk_size);
+
+                debug!("transferring frame {frame} in {} chunk(s)", chunks.len());
+
+                let accepted = Message::JoinAccepted {
+                    transfer: id,
+                    frame,
+                };
+                self.socket.send_to(&accepted.to_bytes(), &peer);
+
+                self.transfer = Some(HostTransfer {
+                    peer,
+                    id,
+                    frame,
+                    acknowledged: vec![false; chunks.len()],
+                    chunks,
+                    cursor: 0,
+                    last_received: Instant::now(),
+                });
+
+                world.send_event(StateTransferEvent::Started { frame });
+            }
+            Err(error) => world.send_event(StateTransferEvent::Failed(error)),
+        }
+    }
+
+    fn encode(world: &World, snapshot: &WorldSnapshot) -> Result<Vec<u8>, StateTransferError> {
+        // The joining peer would refuse to import an incomplete snapshot
+        if !snapshot.omitted.is_empty() {
+            return Err(WorldSnapshotError::Incomplete(snapshot.omitted.clone()).into());
+        }
+
+        let registry = world
+            .get_resource::<AppTypeRegistry>()
+            .ok_or(WorldSnapshotError::MissingTypeRegistry)?
+            .read();
+
+        Ok(snapshot.to_transfer_bytes(&registry)?)
+    }
+
+    fn acknowledge(&mut self, peer: C::Address, id: u32, index: u16) {
+        let Some(transfer) = self.transfer.as_mut() else {
+            return;
+        };
+
+        if transfer.peer != peer || transfer.id != id {
+            return;
+        }
+
+        if let Some(acknowledged) = transfer.acknowledged.get_mut(index as usize) {
+            *acknowledged = true;
+            transfer.last_received = Instant::now();
+        }
+    }
+
+    /// Send the next chunks which have not been acknowledged yet, and start the [`Session`] once
+    /// all of them have been.
+    fn update_transfer(&mut self, world: &mut World) {
+        let Some(transfer) = self.transfer.as_mut() else {
+            return;
+        };
+
+        if transfer
+            .acknowledged
+            .iter()
+            .all(|&acknowledged| acknowledged)
+        {
+            let HostTransfer {
+                peer, id, frame, ..
+            } = self.transfer.take().unwrap();
+
+            match (self.start_session)(&peer) {
+                Ok(session) => {
+                    debug!("transfer of frame {frame} completed");
+                    world.insert_resource(session);
+                    world.send_event(StateTransferEvent::Completed { frame });
+                }
+                Err(error) => world.send_event(StateTransferEvent::Failed(
+                    StateTransferError::Session(error),
+                )),
+            }
+
+            self.completed = Some((peer, id));
+            return;
+        }
+
+        if transfer.last_received.elapsed() > self.timeout {
+            let frame = transfer.frame;
+            self.transfer = None;
+
+            warn!("transfer of frame {frame} timed out");
+            world.send_event(StateTransferEvent::Failed(StateTransferError::TimedOut));
+            return;
+        }
+
+        let count = transfer.chunks.len();
+        let mut sent = 0;
+
+        for offset in 0..count {
+            if sent >= self.chunks_per_update {
+                break;
+            }
+
+            let index = (transfer.cursor + offset) % count;
+
+            if !transfer.acknowledged[index] {
+                let chunk = Message::Chunk(transfer.chunks[index].clone());
+                self.socket.send_to(&chunk.to_bytes(), &transfer.peer);
+                sent += 1;
+                transfer.cursor = index + 1;
+            }
+        }
+    }
+}
+
+/// The progress of a [`StateTransferJoin`].
+enum JoinState {
+    /// Waiting for the host to accept the request.
+    Requesting,
+    Receiving {
+        frame: Option<i32>,
+        assembler: StateTransferAssembler,
+    },
+    /// The state was imported and the [`Session`] started. Chunks which are sent again are still
+    /// acknowledged, in case an acknowledgement was lost.
+    Joined,
+    Failed,
+}
+
+/// A [`Resource`] which requests the state of a game in progress from a [`StateTransferHost`],
+/// see [`StateTransferPlugin`].
+///
+/// The received [`WorldSnapshot`] is imported with [`import_state`], after which the
+/// [`Session`] returned by `start_session` is inserted, continuing from the transferred frame.
+/// The [`World`] must not contain any [`Rollback`](`crate::Rollback`) entities when the state
+/// is imported.
+#[derive(Resource)]
+pub struct StateTransferJoin<C: Config> {
+    socket: Box<dyn StateTransferSocket<C::Address>>,
+    host: C::Address,
+    start_session: StartSession<C>,
+    transfer: u32,
+    state: JoinState,
+}
+
+impl<C: Config> StateTransferJoin<C> {
+    /// Create a request for the state of the game hosted at the provided address, which starts
+    /// the [`Session`] with the host once the state has been imported.
+    pub fn new<F>(
+        socket: impl StateTransferSocket<C::Address> + 'static,
+        host: C::Address,
+        start_session: F,
+    ) -> Self
+    where
+        F: FnMut(&C::Address) -> Result<Session<C>, BoxedError> + Send + Sync + 'static,
+    {
+        // Distinguishes this request from earlier ones, so the host does not repeat a transfer
+        let transfer = RandomState::new().build_hasher().finish() as u32;
+
+        Self {
+            socket: Box::new(socket),
+            host,
+            start_session: Box::new(start_session),
+            transfer,
+            state: JoinState::Requesting,
+        }
+    }
+
+    /// Returns `true` once the state has been imported and the [`Session`] started.
+    pub fn is_joined(&self) -> bool {
+        matches!(self.state, JoinState::Joined)
+    }
+
+    /// The fraction of the state which has been received, from `0.0` to `1.0`.
+    pub fn progress(&self) -> f32 {
+        match &self.state {
+            JoinState::Requesting | JoinState::Failed => 0.,
+            JoinState::Receiving { assembler, .. } => assembler.progress(),
+            JoinState::Joined => 1.,
+        }
+    }
+
+    fn accept(&mut self, world: &mut World, frame: i32) {
+        let started = match &mut self.state {
+            JoinState::Requesting => true,
+            JoinState::Receiving {
+                frame: accepted, ..
+            } => accepted.replace(frame).is_none(),
+            JoinState::Joined | JoinState::Failed => false,
+        };
+
+        if matches!(self.state, JoinState::Requesting) {
+            self.state = JoinState::Receiving {
+                frame: Some(frame),
+                assembler: StateTransferAssembler::default(),
+            };
+        }
+
+        if started {
+            world.send_event(StateTransferEvent::Started { frame });
+        }
+    }
+
+    fn receive_chunk(&mut self, world: &mut World, chunk: StateTransferChunk) {
+        // Without acknowledgements, the host gives up on the transfer
+        if matches!(self.state, JoinState::Failed) {
+            return;
+        }
+
+        let acknowledgement = Message::ChunkReceived {
+            transfer: chunk.transfer,
+            index: chunk.index,
+        };
+        self.socket.send_to(&acknowledgement.to_bytes(), &self.host);
+
+        if matches!(self.state, JoinState::Requesting) {
+            self.state = JoinState::Receiving {
+                frame: None,
+                assembler: StateTransferAssembler::default(),
+            };
+        }
+
+        let JoinState::Receiving { assembler, .. } = &mut self.state else {
+            return;
+        };
+
+        let Some(bytes) = assembler.receive(chunk) else {
+            return;
+        };
+
+        match self.import(world, &bytes) {
+            Ok(frame) => {
+                debug!("joined from frame {frame}");
+                self.state = JoinState::Joined;
+                world.send_event(StateTransferEvent::Completed { frame });
+            }
+            Err(error) => {
+                warn!("failed to join: {error}");
+                self.state = JoinState::Failed;
+                world.send_event(StateTransferEvent::Failed(error));
+            }
+        }
+    }
+
+    fn import(&mut self, world: &mut World, bytes: &[u8]) -> Result<i32, StateTransferError> {
+        let snapshot = {
+            let registry = world
+                .get_resource::<AppTypeRegistry>()
+                .ok_or(WorldSnapshotError::MissingTypeRegistry)?
+                .clone();
+            let registry = registry.read();
+
+            WorldSnapshot::from_transfer_bytes(bytes, &registry)?
+        };
+
+        let session = (self.start_session)(&self.host).map_err(StateTransferError::Session)?;
+
+        import_state(world, &snapshot)?;
+        world.insert_resource(session);
+
+        Ok(snapshot.frame)
+    }
+}
+
+/// A [`Plugin`] which runs the state transfer protocol of a [`StateTransferHost`] or
+/// [`StateTransferJoin`], if either is present, to let peers join a game in progress.
+///
+/// GGRS sessions cannot be joined once they have started, so the protocol replaces the
+/// [`Session`] of both peers:
+/// 1. The joining peer sends a request to the host until it is accepted.
+/// 2. The host removes its [`Session`], rewinds to its most recently confirmed frame with
+///    [`rewind_to_frame`], and accepts the request with that frame.
+/// 3. The host sends the [`WorldSnapshot`] of that frame in [`StateTransferChunk`]s, resending
+///    each one until the joining peer acknowledges it.
+/// 4. The joining peer imports the snapshot with [`import_state`] and starts its [`Session`].
+///    The host starts its [`Session`] once every chunk has been acknowledged.
+///
+/// Both sessions then continue from the agreed frame, see
+/// [`SessionStartFrame`](`crate::SessionStartFrame`). Any other peer of the host's previous
+/// session must also rewind to that frame and join the new one.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, StateTransferJoin, StateTransferPlugin, UdpStateTransferSocket};
+/// # use std::net::SocketAddr;
+/// #
+/// # type MyConfig = GgrsConfig<u8, SocketAddr>;
+/// #
+/// # fn start_session(
+/// #     _host: &SocketAddr,
+/// # ) -> Result<Session<MyConfig>, Box<dyn std::error::Error + Send + Sync>> {
+/// #     unimplemented!()
+/// # }
+/// #
+/// # fn start() -> Result<(), Box<dyn std::error::Error>> {
+/// # let mut app = App::new();
+/// app.add_plugins(StateTransferPlugin::<MyConfig>::default());
+///
+/// let socket = UdpStateTransferSocket::bind_to_port(7001)?;
+/// let host = "127.0.0.1:7000".parse()?;
+///
+/// app.insert_resource(StateTransferJoin::<MyConfig>::new(socket, host, start_session));
+/// # Ok(())
+/// # }
+/// ```
+pub struct StateTransferPlugin<C: Config> {
+    _phantom: PhantomData<C>,
+}
+
+impl<C: Config> Default for StateTransferPlugin<C> {
+    fn default() -> Self {
+        Self {
+            _phantom: default(),
+        }
+    }
+}
+
+impl<C: Config> StateTransferPlugin<C> {
+    /// An exclusive [`System`] which answers requests from joining peers, and sends the state
+    /// of the current transfer.
+    pub fn host(world: &mut World) {
+        let Some(mut host) = world.remove_resource::<StateTransferHost<C>>() else {
+            return;
+        };
+
+        for (peer, bytes) in host.socket.receive_all() {
+            match Message::from_bytes(&bytes) {
+                Some(Message::JoinRequest { transfer }) => host.accept(world, peer, transfer),
+                Some(Message::ChunkReceived { transfer, index }) => {
+                    host.acknowledge(peer, transfer, index)
+                }
+                message => debug!("ignoring state transfer message {message:?}"),
+            }
+        }
+
+        host.update_transfer(world);
+
+        world.insert_resource(host);
+    }
+
+    /// An exclusive [`System`] which requests the state from the host, and imports it once it
+    /// has been received.
+    pub fn join(world: &mut World) {
+        let Some(mut join) = world.remove_resource::<StateTransferJoin<C>>() else {
+            return;
+        };
+
+        for (peer, bytes) in join.socket.receive_all() {
+            if peer != join.host {
+                continue;
+            }
+
+            match Message::from_bytes(&bytes) {
+                Some(Message::JoinAccepted { transfer, frame }) if transfer == join.transfer => {
+                    join.accept(world, frame)
+                }
+                Some(Message::Chunk(chunk)) if chunk.transfer == join.transfer => {
+                    join.receive_chunk(world, chunk)
+                }
+                message => debug!("ignoring state transfer message {message:?}"),
+            }
+        }
+
+        if matches!(join.state, JoinState::Requesting) {
+            let request = Message::JoinRequest {
+                transfer: join.transfer,
+            };
+            join.socket.send_to(&request.to_bytes(), &join.host);
+        }
+
+        world.insert_resource(join);
+    }
+}
+
+impl<C: Config> Plugin for StateTransferPlugin<C> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StateTransferEvent>().add_systems(
+            PreUpdate,
+            (
+                Self::host.run_if(resource_exists::<StateTransferHost<C>>()),
+                Self::join.run_if(resource_exists::<StateTransferJoin<C>>()),
+            )
+                .before(GgrsUpdateSet),
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use crate::RollbackTimingSettings;
+use bevy::prelude::*;
+
+/// Determines how the rollback simulation slows down when this peer is ahead of its remote
+/// peers, giving them time to catch up.
+///
+/// # Examples
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_ggrs::{prelude::*, RollbackTimingSettings, TimeSyncPolicy};
+/// #
+/// # fn start(session: Session<GgrsConfig<u8>>) {
+/// # let mut app = App::new();
+/// /// Never slow down, for games which prefer rollbacks over stutter.
+/// struct NoTimeSync;
+///
+/// impl TimeSyncPolicy for NoTimeSync {
+///     fn frame_time_factor(&mut self, _: i32, _: &RollbackTimingSettings) -> f64 {
+///         1.
+///     }
+/// }
+///
+/// app.set_time_sync_policy(NoTimeSync);
+/// # }
+/// ```
+pub trait TimeSyncPolicy: Send + Sync + 'static {
+    /// Returns how long the next frame should take, as a multiple of the regular frame time,
+    /// given how many frames this peer is ahead of its remote peers.
+    fn frame_time_factor(&mut self, frames_ahead: i32, settings: &RollbackTimingSettings) -> f64;
+}
+
+/// Runs every frame slower by [`RollbackTimingSettings::run_slow_factor`] while this peer is
+/// ahead by any amount. This is the default [`TimeSyncPolicy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RunSlowTimeSync;
+
+impl TimeSyncPolicy for RunSlowTimeSync {
+    fn frame_time_factor(&mut self, frames_ahead: i32, settings: &RollbackTimingSettings) -> f64 {
+        if frames_ahead > 0 {
+            settings.run_slow_factor
+        } else {
+            1.
+        }
+    }
+}
+
+/// Slows down in proportion to how many frames this peer is ahead, so small differences are
+/// corrected gently and large differences quickly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProportionalTimeSync {
+    /// How much longer each frame takes per frame this peer is ahead.
+    pub slowdown_per_frame: f64,
+    /// The largest factor a frame can be slowed down by.
+    pub max_factor: f64,
+}
+
+impl Default for ProportionalTimeSync {
+    fn default() -> Self {
+        Self {
+            slowdown_per_frame: 0.02,
+            max_factor: 1.5,
+        }
+    }
+}
+
+impl TimeSyncPolicy for ProportionalTimeSync {
+    fn frame_time_factor(&mut self, frames_ahead: i32, _settings: &RollbackTimingSettings) -> f64 {
+        let factor = 1. + self.slowdown_per_frame * frames_ahead.max(0) as f64;
+
+        factor.min(self.max_factor.max(1.))
+    }
+}
+
+/// Runs at full speed, but waits for whole frames at once when this peer is ahead. This avoids
+/// any change in simulation speed, at the cost of an occasional hitch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameSkipTimeSync {
+    /// How many frames this peer must be ahead by before skipping.
+    pub threshold: i32,
+    /// How many frames to run at full speed after skipping, as the reported frame advantage
+    /// takes time to update.
+    pub cooldown: u32,
+    remaining_cooldown: u32,
+}
+
+impl FrameSkipTimeSync {
+    pub fn new(threshold: i32, cooldown: u32) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            remaining_cooldown: 0,
+        }
+    }
+}
+
+impl Default for FrameSkipTimeSync {
+    fn default() -> Self {
+        Self::new(2, 60)
+    }
+}
+
+impl TimeSyncPolicy for FrameSkipTimeSync {
+    fn frame_time_factor(&mut self, frames_ahead: i32, _settings: &RollbackTimingSettings) -> f64 {
+        if self.remaining_cooldown > 0 {
+            self.remaining_cooldown -= 1;
+            return 1.;
+        }
+
+        if frames_ahead < self.threshold.max(1) {
+            return 1.;
+        }
+
+        self.remaining_cooldown = self.cooldown;
+
+        // Waiting for the duration of the skipped frames before the next one
+        1. + frames_ahead as f64
+    }
+}
+
+/// The [`TimeSyncPolicy`] used by the [`GgrsPlugin`](`crate::GgrsPlugin`), which can be
+/// replaced using [`set_time_sync_policy`](`crate::GgrsApp::set_time_sync_policy`).
+#[derive(Resource)]
+pub struct RollbackTimeSync {
+    policy: Box<dyn TimeSyncPolicy>,
+}
+
+impl Default for RollbackTimeSync {
+    fn default() -> Self {
+        Self::new(RunSlowTimeSync)
+    }
+}
+
+impl RollbackTimeSync {
+    pub fn new(policy: impl TimeSyncPolicy) -> Self {
+        Self {
+            policy: Box::new(policy),
+        }
+    }
+
+    pub(crate) fn frame_time_factor(
+        &mut self,
+        frames_ahead: i32,
+        settings: &RollbackTimingSettings,
+    ) -> f64 {
+        let factor = self.policy.frame_time_factor(frames_ahead, settings);
+
+        // Peers which are behind catch up as those ahead slow down, so frames are never sped up
+        if factor.is_finite() {
+            factor.max(1.)
+        } else {
+            1.
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use bevy::{
+    ecs::system::EntityCommand,
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Component, Default, Clone, Copy, Hash)]
+struct Health(u32);
+
+#[derive(Resource, Default, Clone, Copy, Hash)]
+struct Score(u32);
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn(mut commands: Commands) {
+    commands.spawn(Health(10)).add_rollback();
+    commands.spawn(Health(20)).add_rollback();
+}
+
+fn update(mut score: ResMut<Score>, mut query: Query<&mut Health>) {
+    score.0 += 1;
+
+    for mut health in query.iter_mut() {
+        health.0 += 1;
+    }
+}
+
+fn report(frame: i32, checksum: u128, parts: Vec<ChecksumReportPart>) -> ChecksumReport {
+    ChecksumReport {
+        frame,
+        checksum,
+        parts,
+    }
+}
+
+fn part(name: &str, checksum: u128, entities: &[(u64, u64)]) -> ChecksumReportPart {
+    let mut world = World::new();
+    let entity = world.spawn_empty().id();
+    AddRollbackCommand.apply(entity, &mut world);
+    let rollback = *world.get::<Rollback>(entity).unwrap();
+
+    ChecksumReportPart {
+        name: name.to_owned(),
+        checksum,
+        entities: entities
+            .iter()
+            .map(|&(order, checksum)| ChecksumReportEntity {
+                rollback,
+                order,
+                checksum,
+            })
+            .collect(),
+    }
+}
+
+fn create_app(report: bool) -> Result<App, Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Score>()
+        .rollback_component_with_copy::<Health>()
+        .rollback_resource_with_copy::<Score>()
+        .checksum_component_with_hash::<Health>()
+        .checksum_resource_with_hash::<Score>()
+        .add_systems(Startup, spawn)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, update);
+
+    if report {
+        app.add_plugins(ChecksumReportPlugin);
+    }
+
+    Ok(app)
+}
+
+/// This test makes sure that a report is recorded for every saved frame, naming every type and
+/// entity which contributed to the checksum.
+#[test]
+fn it_records_checksum_reports() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(true)?;
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    // Every part which contributed to the checksum must be described
+    for report in app.world.resource::<ChecksumHistory>().iter() {
+        assert!(report.parts.iter().all(|part| part.name != "<unknown>"));
+
+        let total = report
+            .parts
+            .iter()
+            .fold(0, |total, part| total ^ part.checksum);
+        assert_eq!(report.checksum, total);
+    }
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let frame = app.world.resource::<RollbackFrameCount>().0;
+    let history = app.world.resource::<ChecksumHistory>();
+    let latest = history.latest().expect("No report was recorded");
+
+    // The latest frame was saved before being advanced
+    assert_eq!(latest.frame, frame - 1);
+    assert_eq!(latest.checksum, app.world.resource::<Checksum>().0);
+    assert_eq!(
+        latest
+            .parts
+            .iter()
+            .map(|part| part.name.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            std::any::type_name::<Health>(),
+            std::any::type_name::<Score>()
+        ]
+    );
+    assert_eq!(latest.parts[0].entities.len(), 2);
+    assert!(latest.parts[1].entities.is_empty());
+
+    // Reports are kept newest first, one per frame
+    let frames = history
+        .iter()
+        .map(|report| report.frame)
+        .collect::<Vec<_>>();
+    assert!(frames.windows(2).all(|frames| frames[0] > frames[1]));
+
+    Ok(())
+}
+
+/// This test makes sure that recording reports does not change the checksum compared by GGRS,
+/// so peers with and without the [`ChecksumReportPlugin`] stay in sync.
+#[test]
+fn it_does_not_change_the_checksum() -> Result<(), Box<dyn std::error::Error>> {
+    let mut with_report = create_app(true)?;
+    let mut without_report = create_app(false)?;
+
+    for _ in 0..30 {
+        with_report.update();
+        without_report.update();
+
+        assert_eq!(
+            with_report.world.resource::<Checksum>().0,
+            without_report.world.resource::<Checksum>().0
+        );
+    }
+
+    Ok(())
+}
+
+/// This test makes sure that resimulated frames replace their previous report, and that only
+/// the configured number of reports are kept.
+#[test]
+fn it_keeps_recent_reports() {
+    let mut history = ChecksumHistory::default();
+    history.set_depth(3);
+
+    for frame in 0..5 {
+        history.push(report(frame, frame as u128, Vec::new()));
+    }
+
+    let frames = history
+        .iter()
+        .map(|report| report.frame)
+        .collect::<Vec<_>>();
+    assert_eq!(frames, vec![4, 3, 2]);
+    assert!(history.get(1).is_none());
+
+    // Rolling back to frame 3 discards the report for frame 4
+    history.push(report(3, 30, Vec::new()));
+
+    let frames = history
+        .iter()
+        .map(|report| report.frame)
+        .collect::<Vec<_>>();
+    assert_eq!(frames, vec![3, 2]);
+    assert_eq!(history.latest().map(|report| report.checksum), Some(30));
+    assert_eq!(history.get(2).map(|report| report.checksum), Some(2));
+}
+
+/// This test makes sure that comparing reports lists exactly the types and entities which diverged.
+#[test]
+fn it_diffs_reports() {
+    let local = report(
+        5,
+        1,
+        vec![
+            part("Health", 10, &[(0, 100), (1, 101), (2, 102)]),
+            part("Local", 20, &[]),
+            part("Score", 30, &[]),
+        ],
+    );
+
+    let remote = report(
+        5,
+        2,
+        vec![
+            part("Health", 11, &[(0, 100), (1, 999), (3, 103)]),
+            part("Remote", 40, &[]),
+            part("Score", 30, &[]),
+        ],
+    );
+
+    assert!(local.diff(&local).is_empty());
+
+    assert_eq!(
+        local.diff(&remote),
+        vec![
+            ChecksumMismatch::Part {
+                name: "Health".to_owned(),
+                local: Some(10),
+                remote: Some(11),
+            },
+            ChecksumMismatch::Entity {
+                name: "Health".to_owned(),
+                order: 1,
+                local: Some(101),
+                remote: Some(999),
+            },
+            ChecksumMismatch::Entity {
+                name: "Health".to_owned(),
+                order: 2,
+                local: Some(102),
+                remote: None,
+            },
+            ChecksumMismatch::Entity {
+                name: "Health".to_owned(),
+                order: 3,
+                local: None,
+                remote: Some(103),
+            },
+            ChecksumMismatch::Part {
+                name: "Local".to_owned(),
+                local: Some(20),
+                remote: None,
+            },
+            ChecksumMismatch::Part {
+                name: "Remote".to_owned(),
+                local: None,
+                remote: Some(40),
+            },
+        ]
+    );
+}
+
+/// This test makes sure that reports survive a round trip through the binary format, and that
+/// corrupt reports are rejected instead of allocating memory for the lengths they claim.
+#[test]
+fn it_reads_written_reports() -> Result<(), Box<dyn std::error::Error>> {
+    let original = report(
+        -3,
+        u128::MAX,
+        vec![
+            part("Health", 10, &[(0, 100), (7, u64::MAX)]),
+            part("Score", 30, &[]),
+        ],
+    );
+
+    let mut bytes = Vec::new();
+    original.write_to(&mut bytes)?;
+
+    // Rollback flags are local to each peer, so are not transferred
+    let read = ChecksumReport::read_from(bytes.as_slice())?;
+    assert_eq!(read.frame, original.frame);
+    assert_eq!(read.checksum, original.checksum);
+    assert!(read.diff(&original).is_empty());
+
+    let mut rewritten = Vec::new();
+    read.write_to(&mut rewritten)?;
+    assert_eq!(rewritten, bytes);
+
+    // Truncated reports are an error
+    assert!(ChecksumReport::read_from(&bytes[..bytes.len() - 1]).is_err());
+
+    // The header is the frame, checksum and number of parts
+    let parts = 20..24;
+    let name_length = 24..28;
+
+    let mut too_many_parts = bytes.clone();
+    too_many_parts[parts].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(ChecksumReport::read_from(too_many_parts.as_slice()).is_err());
+
+    let mut name_too_long = bytes.clone();
+    name_too_long[name_length].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(ChecksumReport::read_from(name_too_long.as_slice()).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+type TestConfig = bevy_ggrs::GgrsConfig<u8, std::net::SocketAddr>;
+
+#[derive(Clone)]
+struct FrameEffect(i32);
+
+#[derive(Resource, Default)]
+struct Dispatched(Vec<i32>);
+
+fn input_system(mut commands: Commands, local_players: Res<LocalPlayers>) {
+    let local_inputs = local_players
+        .0
+        .iter()
+        .map(|&handle| (handle, 0))
+        .collect::<HashMap<_, _>>();
+
+    commands.insert_resource(LocalInputs::<TestConfig>(local_inputs));
+}
+
+fn queue_effect(mut effects: ConfirmedEffectWriter<FrameEffect>, frame: Res<RollbackFrameCount>) {
+    effects.queue(FrameEffect((*frame).into()));
+}
+
+fn collect_effects(
+    mut events: EventReader<ConfirmedEffect<FrameEffect>>,
+    mut dispatched: ResMut<Dispatched>,
+) {
+    for event in events.iter() {
+        assert_eq!(event.frame, event.effect.0);
+        dispatched.0.push(event.frame);
+    }
+}
+
+fn create_app(session: Session<TestConfig>) -> App {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<TestConfig>::default())
+        .add_plugins(ConfirmedEffectPlugin::<FrameEffect>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(session)
+        .init_resource::<Dispatched>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, queue_effect)
+        .add_systems(Update, collect_effects);
+
+    app
+}
+
+/// Asserts that every frame up to the [`ConfirmedFrameCount`] dispatched its effect exactly once.
+fn assert_dispatched_once(app: &App) {
+    let confirmed_frame = i32::from(*app.world.resource::<ConfirmedFrameCount>());
+    let dispatched = &app.world.resource::<Dispatched>().0;
+
+    assert!(confirmed_frame > 10, "No frames were confirmed");
+    assert_eq!(*dispatched, (1..=confirmed_frame).collect::<Vec<_>>());
+}
+
+/// This test makes sure that effects are dispatched exactly once as frames are confirmed by a
+/// SyncTest session, despite every frame being resimulated.
+#[test]
+fn sync_test_dispatches_confirmed_effects() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(4)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = create_app(Session::SyncTest(session));
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>());
+    let confirmed_frame = i32::from(*app.world.resource::<ConfirmedFrameCount>());
+
+    // Frames within the check distance may still be resimulated
+    assert!(confirmed_frame < frame);
+    assert_dispatched_once(&app);
+
+    Ok(())
+}
+
+/// This test makes sure that effects are dispatched exactly once as frames are confirmed by the
+/// remote player of a P2P session.
+#[test]
+fn p2p_dispatches_confirmed_effects() -> Result<(), Box<dyn std::error::Error>> {
+    let create_p2p_app = |local: (PlayerHandle, u16), remote: (PlayerHandle, u16)| {
+        let remote_address = format!("127.0.0.1:{}", remote.1).parse()?;
+        let socket = UdpNonBlockingSocket::bind_to_port(local.1)?;
+        let session = SessionBuilder::<TestConfig>::new()
+            .with_num_players(2)
+            .add_player(PlayerType::Local, local.0)?
+            .add_player(PlayerType::Remote(remote_address), remote.0)?
+            .start_p2p_session(socket)?;
+
+        Ok::<_, Box<dyn std::error::Error>>(create_app(Session::P2P(session)))
+    };
+
+    let mut first = create_p2p_app((0, 8083), (1, 8084))?;
+    let mut second = create_p2p_app((1, 8084), (0, 8083))?;
+
+    for _ in 0..120 {
+        first.update();
+        second.update();
+    }
+
+    assert_dispatched_once(&first);
+    assert_dispatched_once(&second);
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+const ENTITIES: i32 = 10;
+
+#[derive(Component, Clone, Copy, Hash, PartialEq, Debug)]
+struct Slot(i32);
+
+#[derive(Component, Default, Clone, Copy, Hash, PartialEq, Debug)]
+struct Visits(i32);
+
+#[derive(Resource, Default)]
+struct Mismatches(usize);
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn(mut commands: Commands) {
+    for slot in 0..ENTITIES {
+        commands.spawn(Slot(slot)).add_rollback();
+    }
+}
+
+/// Only visits a single entity per frame, so most components are unchanged.
+fn visit(
+    mut commands: Commands,
+    frame: Res<RollbackFrameCount>,
+    mut query: Query<(Entity, &Slot, Option<&mut Visits>)>,
+) {
+    let frame: i32 = (*frame).into();
+
+    for (entity, slot, visits) in query.iter_mut() {
+        if slot.0 != frame % ENTITIES {
+            continue;
+        }
+
+        match visits {
+            // Periodically remove the component to exercise removals
+            Some(visits) if visits.0 % 3 == 2 => {
+                commands.entity(entity).remove::<Visits>();
+            }
+            Some(mut visits) => visits.0 += 1,
+            None => {
+                commands.entity(entity).insert(Visits(1));
+            }
+        }
+    }
+}
+
+fn count_mismatches(mut events: EventReader<SyncTestMismatch>, mut mismatches: ResMut<Mismatches>) {
+    mismatches.0 += events.iter().count();
+}
+
+/// This test makes sure that delta snapshots reconstruct the correct state when rolling back.
+#[test]
+fn delta_snapshots_survive_rollback() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(7)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_plugins(SyncTestReportPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Mismatches>()
+        .rollback_component_with_delta_copy::<Slot>()
+        .rollback_component_with_delta_copy::<Visits>()
+        .checksum_component_with_hash::<Visits>()
+        .add_systems(Startup, spawn)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, visit)
+        .add_systems(Update, count_mismatches);
+
+    for _ in 0..90 {
+        app.update();
+    }
+
+    assert_eq!(
+        app.world.resource::<Mismatches>().0,
+        0,
+        "Rollback was not deterministic"
+    );
+
+    let mut query = app.world.query::<&Visits>();
+    assert!(
+        query.iter(&app.world).count() > 0,
+        "Visits should have been inserted"
+    );
+
+    // The maintained view of the latest snapshot must match a full reconstruction
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>()) - 1;
+    let snapshots = app.world.resource::<GgrsComponentDeltaSnapshots<Visits>>();
+    let reconstructed = snapshots.peek(frame).expect("Snapshot should be stored");
+
+    assert_eq!(snapshots.latest_rollbacks().len(), reconstructed.len());
+
+    for (rollback, visits) in snapshots.iter() {
+        assert_eq!(reconstructed.get(&rollback), Some(&visits));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use bevy::{
+    diagnostic::DiagnosticsStore, prelude::*, time::TimeUpdateStrategy, utils::Duration,
+    utils::HashMap,
+};
+use bevy_ggrs::{prelude::*, *};
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Component, Clone, Copy)]
+struct Health(u32);
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn_players(mut commands: Commands) {
+    commands.spawn(Health(10)).add_rollback();
+    commands.spawn(Health(20)).add_rollback();
+}
+
+/// This test makes sure that rollback metrics and snapshot memory are reported as diagnostics.
+#[test]
+fn diagnostics_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins((GgrsPlugin::<GgrsConfig>::default(), GgrsDiagnosticsPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .rollback_component_with_copy::<Health>()
+        .add_systems(Startup, spawn_players)
+        .add_systems(ReadInputs, input_system);
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let store = app.world.resource::<DiagnosticsStore>();
+
+    let rollbacks = store
+        .get(GgrsDiagnosticsPlugin::ROLLBACKS_PER_SECOND)
+        .and_then(|diagnostic| diagnostic.value());
+    assert!(rollbacks.is_some_and(|rollbacks| rollbacks > 0.));
+
+    // A sync test with a check distance of 2 always resimulates 2 frames
+    let depth = store
+        .get(GgrsDiagnosticsPlugin::MAX_ROLLBACK_DEPTH)
+        .and_then(|diagnostic| diagnostic.value());
+    assert_eq!(depth, Some(2.));
+
+    let memory = store
+        .get(GgrsDiagnosticsPlugin::snapshot_memory(
+            std::any::type_name::<Health>(),
+        ))
+        .and_then(|diagnostic| diagnostic.value());
+    assert!(memory.is_some_and(|memory| memory > 0.));
+
+    Ok(())
+}
+
+/// This test makes sure that snapshot memory is not measured without a running session.
+#[test]
+fn snapshot_memory_requires_a_session() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins((GgrsPlugin::<GgrsConfig>::default(), GgrsDiagnosticsPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .rollback_component_with_copy::<Health>()
+        .add_systems(Startup, spawn_players)
+        .add_systems(ReadInputs, input_system);
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let memory = app
+        .world
+        .resource::<DiagnosticsStore>()
+        .get(GgrsDiagnosticsPlugin::snapshot_memory(
+            std::any::type_name::<Health>(),
+        ))
+        .and_then(|diagnostic| diagnostic.value());
+    assert_eq!(memory, None);
+}
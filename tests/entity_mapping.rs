@@ -21,11 +21,9 @@ struct ParentEntity;
 #[derive(Reflect, Resource, Default, Debug)]
 struct FrameCounter(u16);
 
-fn input_system(mut commands: Commands, mut delete_events: EventReader<DeleteChildEntityEvent>) {
-    let should_delete = u8::from(delete_events.iter().count() > 0);
-
+fn input_system(mut commands: Commands) {
     let mut local_inputs = HashMap::new();
-    local_inputs.insert(0, should_delete);
+    local_inputs.insert(0, 0);
 
     commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
 }
@@ -39,21 +37,29 @@ fn setup_system(mut commands: Commands) {
         });
 }
 
+fn request_delete_system(
+    counter: Res<FrameCounter>,
+    mut delete_events: RollbackEventWriter<DeleteChildEntityEvent>,
+) {
+    if counter.0 == DELETE_CHILD_FRAME {
+        println!("Requesting child deletion");
+        delete_events.send(DeleteChildEntityEvent);
+    }
+}
+
 fn delete_child_system(
     mut commands: Commands,
-    inputs: Res<PlayerInputs<GgrsConfig>>,
+    delete_events: RollbackEventReader<DeleteChildEntityEvent>,
     parent: Query<&Children, With<ParentEntity>>,
     child: Query<Entity, With<ChildEntity>>,
 ) {
-    println!("Inputs: {:?}", **inputs);
-
     println!("Parent's children: {:?}", parent.single());
 
     if let Ok(child) = child.get_single() {
         println!("Child exists: {child:?}");
     }
 
-    if inputs[0].0 == 1 {
+    if !delete_events.is_empty() {
         println!("Despawning child");
         let child_entity = parent.single()[0];
         commands.entity(child_entity).despawn();
@@ -65,7 +71,10 @@ fn frame_counter(mut counter: ResMut<FrameCounter>) {
     counter.0 = counter.0.wrapping_add(1);
 }
 
-#[derive(Event)]
+/// The frame during which the deletion of the child entity is requested.
+const DELETE_CHILD_FRAME: u16 = 3;
+
+#[derive(Clone)]
 struct DeleteChildEntityEvent;
 
 /// This test makes sure that we correctly map entities stored in resource and components during
@@ -76,7 +85,6 @@ fn entity_mapping() {
 
     app.add_plugins(MinimalPlugins)
         .add_plugins(TransformPlugin)
-        .add_event::<DeleteChildEntityEvent>()
         .init_resource::<FrameCounter>()
         .add_systems(Startup, setup_system)
         // Insert the GGRS session
@@ -95,7 +103,11 @@ fn entity_mapping() {
         .rollback_component_with_reflect::<ChildEntity>()
         .rollback_component_with_reflect::<ParentEntity>()
         .rollback_resource_with_reflect::<FrameCounter>()
-        .add_systems(GgrsSchedule, (frame_counter, delete_child_system).chain());
+        .add_rollback_event::<DeleteChildEntityEvent>()
+        .add_systems(
+            GgrsSchedule,
+            (frame_counter, request_delete_system, delete_child_system).chain(),
+        );
 
     // Sleep helper that will make sure at least one frame should be executed by the GGRS fixed
     // update loop.
@@ -121,16 +133,9 @@ fn entity_mapping() {
         "Parent doesn't exist"
     );
 
-    sleep();
-    app.update();
-
-    // Send the event to delete the child entity
-    app.world
-        .resource_mut::<Events<DeleteChildEntityEvent>>()
-        .send(DeleteChildEntityEvent);
-
-    // Run for a number of times to make sure we get some rollbacks to happen
-    for _ in 0..5 {
+    // Run for a number of times to make sure the child is deleted, and we get some rollbacks to
+    // happen afterwards
+    for _ in 0..(DELETE_CHILD_FRAME + 5) {
         sleep();
         app.update();
     }
@@ -0,0 +1,184 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap, HashSet},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Component, Clone, Copy, Hash)]
+struct Lifetime(u32);
+
+/// Every [`Entity`] which was ever simulated with a [`Lifetime`].
+#[derive(Resource, Default)]
+struct Simulated(HashSet<Entity>);
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn(mut commands: Commands) {
+    commands.spawn(Lifetime(5)).add_rollback();
+}
+
+#[derive(Component)]
+struct Family;
+
+fn spawn_family(mut commands: Commands) {
+    commands.spawn(Family).with_children(|parent| {
+        parent.spawn(Lifetime(5)).add_rollback();
+        parent.spawn(Lifetime(20)).add_rollback();
+    });
+}
+
+fn expire(
+    mut commands: Commands,
+    mut simulated: ResMut<Simulated>,
+    mut query: Query<(Entity, &mut Lifetime)>,
+) {
+    for (entity, mut lifetime) in query.iter_mut() {
+        simulated.0.insert(entity);
+        lifetime.0 -= 1;
+
+        if lifetime.0 == 0 {
+            commands.entity(entity).despawn_rollback();
+        }
+    }
+}
+
+fn create_app(plugin: GgrsPlugin<GgrsConfig>, session: Session<GgrsConfig>) -> App {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(plugin)
+        .add_plugins(RollbackEntityPoolPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(session)
+        .init_resource::<Simulated>()
+        .rollback_component_with_copy::<Lifetime>()
+        .checksum_component_with_hash::<Lifetime>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, expire);
+
+    app
+}
+
+fn start_synctest_session() -> Result<Session<GgrsConfig>, GgrsError> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    Ok(Session::SyncTest(session))
+}
+
+/// This test makes sure that entities despawned and then restored by a rollback are revived
+/// with the same [`Entity`], and are eventually despawned for good.
+#[test]
+fn pooled_entities_are_revived() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(GgrsPlugin::default(), start_synctest_session()?);
+    app.add_systems(Startup, spawn);
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    let simulated = &app.world.resource::<Simulated>().0;
+
+    assert_eq!(
+        simulated.len(),
+        1,
+        "Rolled back entities should be revived rather than respawned"
+    );
+
+    let entity = *simulated.iter().next().unwrap();
+
+    assert!(
+        app.world.get_entity(entity).is_none(),
+        "Pooled entities should be despawned once they can no longer be restored"
+    );
+    assert!(app.world.resource::<RollbackEntityPool>().is_empty());
+
+    Ok(())
+}
+
+/// This test makes sure that pooled entities are also despawned by sessions which never save
+/// snapshots, such as replays.
+#[test]
+fn pooled_entities_expire_without_rollback() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(GgrsPlugin::default(), start_synctest_session()?);
+    app.add_systems(Startup, spawn)
+        .init_resource::<InputRecording<GgrsConfig>>();
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    let recording = app
+        .world
+        .remove_resource::<InputRecording<GgrsConfig>>()
+        .unwrap();
+
+    let mut app = create_app(
+        GgrsPlugin::default(),
+        Session::Replay(ReplaySession::new(recording)),
+    );
+    app.add_systems(Startup, spawn);
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    let simulated = &app.world.resource::<Simulated>().0;
+    assert_eq!(simulated.len(), 1);
+
+    let entity = *simulated.iter().next().unwrap();
+
+    assert!(
+        app.world.get_entity(entity).is_none(),
+        "Pooled entities should be despawned once they can no longer be restored"
+    );
+    assert!(app.world.resource::<RollbackEntityPool>().is_empty());
+
+    Ok(())
+}
+
+/// This test makes sure that pooled entities are removed from the [`Children`] of their parent,
+/// even without hierarchy rollback.
+#[test]
+fn pooled_entities_leave_their_parent() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(
+        GgrsPlugin::default().without_hierarchy_rollback(),
+        start_synctest_session()?,
+    );
+
+    app.add_systems(Startup, spawn_family);
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    let parent = app
+        .world
+        .query_filtered::<Entity, With<Family>>()
+        .single(&app.world);
+    let children = app.world.get::<Children>(parent).unwrap();
+
+    assert_eq!(children.len(), 1, "Only the living child should remain");
+    assert!(app.world.get::<PooledRollback>(children[0]).is_none());
+
+    Ok(())
+}
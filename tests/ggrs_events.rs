@@ -0,0 +1,73 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration};
+use bevy_ggrs::{prelude::*, *};
+use ggrs::*;
+
+type TestConfig = bevy_ggrs::GgrsConfig<u8, std::net::SocketAddr>;
+
+/// The number of `Synchronized` events seen by each reader.
+#[derive(Resource, Default)]
+struct Synchronized([usize; 2]);
+
+fn input_system(mut commands: Commands, local_players: Res<LocalPlayers>) {
+    let local_inputs = local_players.0.iter().map(|&handle| (handle, 0)).collect();
+
+    commands.insert_resource(LocalInputs::<TestConfig>(local_inputs));
+}
+
+fn read_events<const READER: usize>(
+    mut events: EventReader<GgrsSessionEvent<TestConfig>>,
+    mut synchronized: ResMut<Synchronized>,
+) {
+    for event in events.iter() {
+        if let GgrsEvent::Synchronized { .. } = **event {
+            synchronized.0[READER] += 1;
+        }
+    }
+}
+
+fn create_app(
+    local: (PlayerHandle, u16),
+    remote: (PlayerHandle, u16),
+) -> Result<App, Box<dyn std::error::Error>> {
+    let remote_address = format!("127.0.0.1:{}", remote.1).parse()?;
+    let socket = UdpNonBlockingSocket::bind_to_port(local.1)?;
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(2)
+        .add_player(PlayerType::Local, local.0)?
+        .add_player(PlayerType::Remote(remote_address), remote.0)?
+        .start_p2p_session(socket)?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<TestConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::P2P(session))
+        .init_resource::<Synchronized>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(Update, (read_events::<0>, read_events::<1>).chain());
+
+    Ok(app)
+}
+
+/// This test makes sure that GGRS events are forwarded to every Bevy event reader.
+#[test]
+fn events_are_forwarded_to_every_reader() -> Result<(), Box<dyn std::error::Error>> {
+    let mut first = create_app((0, 8097), (1, 8098))?;
+    let mut second = create_app((1, 8098), (0, 8097))?;
+
+    for _ in 0..200 {
+        first.update();
+        second.update();
+    }
+
+    for app in [&first, &second] {
+        let synchronized = app.world.resource::<Synchronized>();
+
+        assert_eq!(synchronized.0, [1, 1]);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use bevy::{
+    ecs::entity::{EntityMapper, MapEntities},
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+/// Marks the non-rollback child, such as a visual effect attached to a rollback entity.
+#[derive(Component)]
+struct Decoration;
+
+/// Marks the rollback child whose global transform is checked.
+#[derive(Component)]
+struct Wheel;
+
+/// A custom relationship from a tethered entity to its anchor.
+#[derive(Component, Reflect, Clone, Copy)]
+struct Tether(Entity);
+
+impl FromWorld for Tether {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for Tether {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.0 = entity_mapper.get_or_reserve(self.0);
+    }
+}
+
+/// The entities tethered to an anchor.
+#[derive(Component, Reflect, Default, Clone)]
+struct Tethered(Vec<Entity>);
+
+impl MapEntities for Tethered {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        for entity in &mut self.0 {
+            *entity = entity_mapper.get_or_reserve(*entity);
+        }
+    }
+}
+
+impl RollbackRelationship for Tether {
+    type Children = Tethered;
+
+    fn parent(&self) -> Entity {
+        self.0
+    }
+
+    fn children(children: &Tethered) -> &[Entity] {
+        &children.0
+    }
+
+    fn set_children(world: &mut World, parent: Entity, children: &[Entity]) {
+        if children.is_empty() {
+            world.entity_mut(parent).remove::<Tethered>();
+        } else {
+            world.entity_mut(parent).insert(Tethered(children.to_vec()));
+        }
+    }
+}
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn_parent(mut commands: Commands, frame: Res<RollbackFrameCount>) {
+    if i32::from(*frame) != 3 {
+        return;
+    }
+
+    commands
+        .spawn(TransformBundle::from_transform(Transform::from_xyz(
+            1., 0., 0.,
+        )))
+        .add_rollback()
+        .with_children(|parent| {
+            parent.spawn((
+                Decoration,
+                TransformBundle::from_transform(Transform::from_xyz(0., 2., 0.)),
+            ));
+        });
+}
+
+fn spawn_anchor(mut commands: Commands, frame: Res<RollbackFrameCount>) {
+    if i32::from(*frame) != 3 {
+        return;
+    }
+
+    let anchor = commands.spawn_empty().add_rollback().id();
+    let decoration = commands.spawn((Decoration, Tether(anchor))).id();
+
+    commands.entity(anchor).insert(Tethered(vec![decoration]));
+}
+
+fn spawn_vehicle(mut commands: Commands) {
+    commands
+        .spawn(TransformBundle::default())
+        .add_rollback()
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Wheel,
+                    TransformBundle::from_transform(Transform::from_xyz(0., 2., 0.)),
+                ))
+                .add_rollback();
+        });
+}
+
+fn drive(frame: Res<RollbackFrameCount>, mut query: Query<&mut Transform, Without<Parent>>) {
+    for mut transform in query.iter_mut() {
+        transform.translation.x = i32::from(*frame) as f32;
+    }
+}
+
+/// This test makes sure that non-rollback children are despawned along with their rollback
+/// parent, and that the repaired hierarchy propagates global transforms.
+#[test]
+fn hierarchy_is_repaired_after_rollback() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_plugins(TransformPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .rollback_component_with_copy::<Transform>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, spawn_parent);
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let mut decorations = app
+        .world
+        .query_filtered::<(&Parent, &GlobalTransform), With<Decoration>>();
+
+    let decorations = decorations.iter(&app.world).collect::<Vec<_>>();
+
+    assert_eq!(
+        decorations.len(),
+        1,
+        "Decorations of rolled back parents should be despawned"
+    );
+
+    let (parent, global_transform) = decorations[0];
+
+    assert!(app.world.get::<Rollback>(parent.get()).is_some());
+    assert_eq!(global_transform.translation(), Vec3::new(1., 2., 0.));
+
+    Ok(())
+}
+
+/// This test makes sure that user-defined relationships are repaired like the hierarchy.
+#[test]
+fn custom_relationship_is_repaired_after_rollback() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_plugins(RelationshipSnapshotPlugin::<Tether>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, spawn_anchor);
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let mut decorations = app
+        .world
+        .query_filtered::<(Entity, &Tether), With<Decoration>>();
+
+    let decorations = decorations.iter(&app.world).collect::<Vec<_>>();
+
+    assert_eq!(
+        decorations.len(),
+        1,
+        "Decorations of rolled back anchors should be despawned"
+    );
+
+    let (decoration, tether) = decorations[0];
+
+    assert!(app.world.get::<Rollback>(tether.0).is_some());
+    assert_eq!(
+        app.world
+            .get::<Tethered>(tether.0)
+            .map(|tethered| &tethered.0),
+        Some(&vec![decoration])
+    );
+
+    Ok(())
+}
+
+/// This test makes sure that global transforms of rollback hierarchies are propagated as soon as
+/// a snapshot is loaded, rather than waiting for the [`TransformPlugin`].
+#[test]
+fn global_transforms_are_propagated_on_load() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_plugins(TransformPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .rollback_component_with_copy::<Transform>()
+        .add_systems(Startup, spawn_vehicle)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, drive);
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    app.world.remove_resource::<Session<GgrsConfig>>();
+
+    let mut vehicles = app
+        .world
+        .query_filtered::<&Transform, (With<Rollback>, Without<Parent>)>();
+
+    let before = vehicles.single(&app.world).translation;
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>()) - 1;
+
+    rewind_to_frame(&mut app.world, frame)?;
+
+    let restored = vehicles.single(&app.world).translation;
+
+    assert_ne!(
+        before, restored,
+        "The vehicle should have moved between frames"
+    );
+
+    let mut wheels = app.world.query_filtered::<&GlobalTransform, With<Wheel>>();
+
+    assert_eq!(
+        wheels.single(&app.world).translation(),
+        restored + Vec3::new(0., 2., 0.)
+    );
+
+    Ok(())
+}
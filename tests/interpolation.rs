@@ -0,0 +1,127 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq)]
+struct Position(f32);
+
+impl Interpolate for Position {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self(self.0.interpolate(&other.0, t))
+    }
+}
+
+/// The [`Position`] at which entities stop moving.
+#[derive(Resource)]
+struct Destination(f32);
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn(mut commands: Commands) {
+    commands.spawn(Position::default()).add_rollback();
+}
+
+fn move_towards_destination(destination: Res<Destination>, mut query: Query<&mut Position>) {
+    for mut position in query.iter_mut() {
+        if position.0 < destination.0 {
+            position.0 += 1.;
+        }
+    }
+}
+
+fn create_app(destination: f32) -> Result<App, Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_plugins(InterpolationPlugin::<Position>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .insert_resource(Destination(destination))
+        .rollback_component_with_copy::<Position>()
+        .add_systems(Startup, spawn)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, move_towards_destination);
+
+    Ok(app)
+}
+
+fn interpolation_state(app: &mut App) -> (Position, InterpolationState<Position>, Position) {
+    let mut query = app.world.query::<(
+        &Position,
+        &InterpolationState<Position>,
+        &Interpolated<Position>,
+    )>();
+
+    let (&position, state, interpolated) = query.single(&app.world);
+
+    (position, state.clone(), interpolated.0)
+}
+
+/// This test makes sure that moving entities are interpolated between the previous and the
+/// current simulated value.
+#[test]
+fn it_interpolates_moving_entities() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(f32::MAX)?;
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let (position, state, interpolated) = interpolation_state(&mut app);
+    let fraction = app.world.resource::<RollbackTimestepFraction>().0;
+
+    assert!(position.0 > 0., "Simulation did not advance");
+    assert_eq!(state.current, position);
+    assert!(state.previous.0 < state.current.0);
+    assert_eq!(
+        interpolated,
+        state.previous.interpolate(&state.current, fraction)
+    );
+
+    Ok(())
+}
+
+/// This test makes sure that entities which stop changing come to rest, instead of repeating
+/// their last movement.
+#[test]
+fn it_rests_unchanged_entities() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(5.)?;
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    let (position, state, interpolated) = interpolation_state(&mut app);
+
+    assert_eq!(position, Position(5.));
+    assert_eq!(state.previous, position);
+    assert_eq!(state.current, position);
+    assert_eq!(interpolated, position);
+
+    Ok(())
+}
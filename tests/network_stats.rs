@@ -0,0 +1,113 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, Instant},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+type TestConfig = bevy_ggrs::GgrsConfig<u8, std::net::SocketAddr>;
+
+fn input_system(mut commands: Commands, local_players: Res<LocalPlayers>) {
+    let local_inputs = local_players.0.iter().map(|&handle| (handle, 0)).collect();
+
+    commands.insert_resource(LocalInputs::<TestConfig>(local_inputs));
+}
+
+fn create_app(
+    local: (PlayerHandle, u16),
+    remote: (PlayerHandle, u16),
+) -> Result<App, Box<dyn std::error::Error>> {
+    let remote_address = format!("127.0.0.1:{}", remote.1).parse()?;
+    let socket = UdpNonBlockingSocket::bind_to_port(local.1)?;
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(2)
+        .add_player(PlayerType::Local, local.0)?
+        .add_player(PlayerType::Remote(remote_address), remote.0)?
+        .start_p2p_session(socket)?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<TestConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(GgrsNetworkStats::new(5))
+        .insert_resource(Session::P2P(session))
+        .add_systems(ReadInputs, input_system);
+
+    Ok(app)
+}
+
+/// Update both apps until every app has stats for its remote player. GGRS only reports stats
+/// once a session has been running for a second, so this waits on the condition rather than
+/// for a fixed number of updates.
+fn update_until_stats(mut apps: [(&mut App, PlayerHandle); 2]) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+
+    while apps.iter().any(|(app, remote)| {
+        app.world
+            .resource::<GgrsNetworkStats>()
+            .get(*remote)
+            .is_none()
+    }) {
+        assert!(
+            Instant::now() < deadline,
+            "Network stats were not reported in time"
+        );
+
+        for (app, _) in apps.iter_mut() {
+            app.update();
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// This test makes sure that the network stats of remote players are available as a resource,
+/// with a limited history.
+#[test]
+fn network_stats_are_recorded() -> Result<(), Box<dyn std::error::Error>> {
+    let mut first = create_app((0, 8099), (1, 8100))?;
+    let mut second = create_app((1, 8100), (0, 8099))?;
+
+    update_until_stats([(&mut first, 1), (&mut second, 0)]);
+
+    // Every update with stats available is recorded, up to the history length
+    for _ in 0..5 {
+        first.update();
+        second.update();
+    }
+
+    for (app, remote) in [(&first, 1), (&second, 0)] {
+        let network_stats = app.world.resource::<GgrsNetworkStats>();
+
+        assert!(network_stats.get(remote).is_some());
+        assert_eq!(network_stats.iter().count(), 1);
+        assert_eq!(network_stats.history(remote).count(), 5);
+    }
+
+    // Stats are cleared once the session is removed
+    first.world.remove_resource::<Session<TestConfig>>();
+    first.update();
+
+    let network_stats = first.world.resource::<GgrsNetworkStats>();
+    assert!(network_stats.get(1).is_none());
+    assert_eq!(network_stats.history(1).count(), 0);
+
+    // Stats are also cleared when replaced by a session without remote peers
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(1)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    second.insert_resource(Session::SyncTest(session));
+    second.update();
+
+    let network_stats = second.world.resource::<GgrsNetworkStats>();
+    assert!(network_stats.get(0).is_none());
+    assert_eq!(network_stats.history(0).count(), 0);
+
+    Ok(())
+}
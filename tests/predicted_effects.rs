@@ -0,0 +1,257 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Effect(u32);
+
+#[derive(Resource, Default)]
+struct Received(Vec<PredictedEffectEvent<Effect>>);
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn collect_events(
+    mut events: EventReader<PredictedEffectEvent<Effect>>,
+    mut received: ResMut<Received>,
+) {
+    received.0.extend(events.iter().cloned());
+}
+
+fn start_session() -> Result<SyncTestSession<GgrsConfig>, Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(4)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    Ok(session)
+}
+
+fn create_app<M>(
+    frames_per_update: u32,
+    declare: impl IntoSystemConfigs<M>,
+) -> Result<App, Box<dyn std::error::Error>> {
+    let session = start_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_plugins(PredictedEffectPlugin::<Effect>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            frames_per_update as f64 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Received>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, declare)
+        .add_systems(Update, collect_events);
+
+    Ok(app)
+}
+
+fn started(app: &App) -> Vec<(Effect, i32)> {
+    app.world
+        .resource::<Received>()
+        .0
+        .iter()
+        .filter_map(|event| match *event {
+            PredictedEffectEvent::Started { key, frame } => Some((key, frame)),
+            PredictedEffectEvent::Cancelled { .. } => None,
+        })
+        .collect()
+}
+
+fn cancelled(app: &App) -> Vec<(Effect, i32)> {
+    app.world
+        .resource::<Received>()
+        .0
+        .iter()
+        .filter_map(|event| match *event {
+            PredictedEffectEvent::Cancelled { key, frame } => Some((key, frame)),
+            PredictedEffectEvent::Started { .. } => None,
+        })
+        .collect()
+}
+
+/// This test makes sure that an effect declared on every frame is started exactly once, even
+/// once it lasts longer than the frames which may still be rolled back.
+#[test]
+fn continuous_effects_start_once() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(1, |mut effects: PredictedEffectWriter<Effect>| {
+        effects.declare(Effect(0));
+    })?;
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let confirmed_frame = i32::from(*app.world.resource::<ConfirmedFrameCount>());
+
+    assert!(confirmed_frame > 10, "No frames were confirmed");
+    assert_eq!(started(&app), vec![(Effect(0), 1)]);
+    assert!(cancelled(&app).is_empty());
+
+    Ok(())
+}
+
+/// This test makes sure that declaring a key again after a frame without it starts a new effect,
+/// and that resimulating the same declarations does not start or cancel anything.
+#[test]
+fn repeated_effects_start_again() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(
+        1,
+        |mut effects: PredictedEffectWriter<Effect>, frame: Res<RollbackFrameCount>| {
+            if i32::from(*frame) % 10 < 3 {
+                effects.declare(Effect(1));
+            }
+        },
+    )?;
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>());
+    let expected = (1..=frame)
+        .filter(|frame| frame % 10 == 0 || *frame == 1)
+        .map(|frame| (Effect(1), frame))
+        .collect::<Vec<_>>();
+
+    assert!(frame > 10, "Simulation did not advance");
+    assert_eq!(started(&app), expected);
+    assert!(cancelled(&app).is_empty());
+
+    Ok(())
+}
+
+/// This test makes sure that effects which are not declared again when a frame is resimulated
+/// are cancelled.
+#[test]
+fn mispredicted_effects_are_cancelled() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(
+        1,
+        (|mut effects: PredictedEffectWriter<Effect>, frame: Res<RollbackFrameCount>| {
+            effects.declare(Effect(i32::from(*frame) as u32));
+        })
+        .run_if(is_first_simulation()),
+    )?;
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let started = started(&app);
+    let cancelled = cancelled(&app);
+
+    assert!(!cancelled.is_empty(), "No effects were cancelled");
+
+    // Every effect is started once, then cancelled at most once when its frame is resimulated
+    for (index, effect) in started.iter().enumerate() {
+        assert!(
+            !started[..index].contains(effect),
+            "{effect:?} started twice"
+        );
+    }
+
+    for (index, effect) in cancelled.iter().enumerate() {
+        assert!(started.contains(effect), "{effect:?} was never started");
+        assert!(
+            !cancelled[..index].contains(effect),
+            "{effect:?} cancelled twice"
+        );
+    }
+
+    Ok(())
+}
+
+/// This test makes sure that effects are compared after every batch of requests, so an effect
+/// which is declared and cancelled while several frames are advanced in one update is still sent.
+#[test]
+fn effects_are_sent_for_every_advanced_frame() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(
+        3,
+        (|mut effects: PredictedEffectWriter<Effect>, frame: Res<RollbackFrameCount>| {
+            effects.declare(Effect(i32::from(*frame) as u32));
+        })
+        .run_if(is_first_simulation()),
+    )?;
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>());
+    let started = started(&app);
+    let cancelled = cancelled(&app);
+
+    assert!(frame > 20, "Several frames were not advanced per update");
+
+    // Every frame declares an effect during its first simulation, which is started right away
+    let expected = (1..=frame)
+        .map(|frame| (Effect(frame as u32), frame))
+        .collect::<Vec<_>>();
+
+    assert_eq!(started, expected);
+    assert!(!cancelled.is_empty(), "No effects were cancelled");
+
+    for effect in cancelled.iter() {
+        assert!(started.contains(effect), "{effect:?} was never started");
+    }
+
+    Ok(())
+}
+
+/// This test makes sure that effects started during a previous session are forgotten, and are
+/// started again when they are declared during a new session.
+#[test]
+fn effects_do_not_leak_into_the_next_session() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(1, |mut effects: PredictedEffectWriter<Effect>| {
+        effects.declare(Effect(0));
+    })?;
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    assert_eq!(started(&app), vec![(Effect(0), 1)]);
+
+    app.world.remove_resource::<Session<GgrsConfig>>();
+    app.update();
+
+    assert!(app
+        .world
+        .resource::<ActivePredictedEffects<Effect>>()
+        .is_empty());
+
+    app.world
+        .insert_resource(Session::SyncTest(start_session()?));
+    app.world.insert_resource(RollbackFrameCount::default());
+    app.world
+        .insert_resource(PredictedEffects::<Effect>::default());
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    assert_eq!(started(&app), vec![(Effect(0), 1), (Effect(0), 1)]);
+    assert!(cancelled(&app).is_empty());
+
+    Ok(())
+}
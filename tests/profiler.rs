@@ -0,0 +1,71 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration, utils::HashMap};
+use bevy_ggrs::{prelude::*, *};
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn move_players() {}
+
+fn animate_players() {}
+
+/// This test makes sure that profiled systems are timed separately for first simulation and
+/// resimulation.
+#[test]
+fn profiler_distinguishes_resimulation() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins((GgrsPlugin::<GgrsConfig>::default(), GgrsProfilerPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .add_systems(ReadInputs, input_system)
+        .add_systems(
+            GgrsSchedule,
+            (profiled(move_players), profiled(animate_players)).chain(),
+        );
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let profiler = app.world.resource::<GgrsProfiler>();
+    let report = profiler.report();
+
+    assert_eq!(report.len(), 2);
+
+    let frames = i32::from(*app.world.resource::<RollbackFrameCount>()) as u32;
+
+    for system in report.iter() {
+        assert_eq!(system.first_simulation.runs, frames);
+        assert!(system.resimulation.runs > 0);
+    }
+
+    let schedule = profiler.schedule();
+    assert_eq!(schedule.first_simulation.runs, frames);
+    assert_eq!(schedule.resimulation.runs, report[0].resimulation.runs);
+
+    app.world.resource_mut::<GgrsProfiler>().reset();
+    assert!(app.world.resource::<GgrsProfiler>().report().is_empty());
+
+    Ok(())
+}
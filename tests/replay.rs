@@ -0,0 +1,185 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+struct InputSum(u32);
+
+fn input_system(mut commands: Commands, mut counter: Local<u8>) {
+    *counter = counter.wrapping_add(1);
+
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, *counter % 7);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn sum_inputs(mut sum: ResMut<InputSum>, inputs: Res<PlayerInputs<GgrsConfig>>) {
+    sum.0 += inputs[0].0 as u32;
+}
+
+fn create_app(session: Session<GgrsConfig>) -> App {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .init_resource::<InputSum>()
+        .rollback_resource_with_copy::<InputSum>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, sum_inputs)
+        .insert_resource(session);
+
+    app
+}
+
+/// This test makes sure that a recorded session can be saved, loaded and replayed to
+/// produce the same simulation result.
+#[test]
+fn it_replays_recorded_inputs() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = create_app(Session::SyncTest(session));
+    app.init_resource::<InputRecording<GgrsConfig>>();
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let recording = app
+        .world
+        .remove_resource::<InputRecording<GgrsConfig>>()
+        .unwrap();
+    let recorded_sum = *app.world.resource::<InputSum>();
+    let recorded_frames = recording.len();
+
+    assert!(recorded_frames > 0, "No frames were recorded");
+    assert!(recorded_sum.0 > 0, "No inputs were simulated");
+
+    // Round-trip through the binary format
+    let mut bytes = Vec::new();
+    recording.write_to(&mut bytes)?;
+    let recording = InputRecording::<GgrsConfig>::read_from(bytes.as_slice())?;
+
+    assert_eq!(recording.len(), recorded_frames);
+
+    let mut app = create_app(Session::Replay(ReplaySession::new(recording)));
+
+    // Run well past the end of the recording
+    for _ in 0..(recorded_frames * 2) {
+        app.update();
+    }
+
+    let Session::Replay(replay) = app.world.resource::<Session<GgrsConfig>>() else {
+        panic!("Session is not a replay");
+    };
+    assert!(replay.is_finished(), "Replay did not finish");
+
+    assert_eq!(*app.world.resource::<InputSum>(), recorded_sum);
+
+    Ok(())
+}
+
+/// This test makes sure that a session continuing from a later [`SessionStartFrame`] is recorded
+/// and replayed on the frames it was simulated on.
+#[test]
+fn it_replays_from_the_session_start_frame() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = create_app(Session::SyncTest(session));
+    let session = app.world.remove_resource::<Session<GgrsConfig>>().unwrap();
+
+    // Without a session, the frame count is reset to the session start frame
+    app.insert_resource(SessionStartFrame(100));
+    app.update();
+
+    app.insert_resource(session)
+        .init_resource::<InputRecording<GgrsConfig>>();
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    let recording = app
+        .world
+        .remove_resource::<InputRecording<GgrsConfig>>()
+        .unwrap();
+    let recorded_sum = *app.world.resource::<InputSum>();
+    let recorded_frames = recording.len();
+    let last_frame = recording.end_frame() - 1;
+
+    assert_eq!(recording.start_frame(), 101);
+    assert_eq!(
+        last_frame,
+        i32::from(*app.world.resource::<RollbackFrameCount>())
+    );
+
+    let mut app = create_app(Session::Replay(ReplaySession::new(recording)));
+
+    for _ in 0..(recorded_frames * 2) {
+        app.update();
+    }
+
+    assert_eq!(
+        i32::from(*app.world.resource::<RollbackFrameCount>()),
+        last_frame
+    );
+    assert_eq!(*app.world.resource::<InputSum>(), recorded_sum);
+
+    Ok(())
+}
+
+/// This test makes sure that a corrupt replay header is rejected instead of allocating memory
+/// for the number of frames or players it claims.
+#[test]
+fn it_rejects_corrupt_replays() -> Result<(), Box<dyn std::error::Error>> {
+    let mut recording = InputRecording::<GgrsConfig>::default();
+    recording.record(0, &[(1, InputStatus::Confirmed)]);
+
+    let mut bytes = Vec::new();
+    recording.write_to(&mut bytes)?;
+
+    // The header is the magic, version, input size, players, start frame and frames
+    let players = 16..20;
+    let frames = 24..28;
+
+    let mut too_many_players = bytes.clone();
+    too_many_players[players.clone()].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(InputRecording::<GgrsConfig>::read_from(too_many_players.as_slice()).is_err());
+
+    let mut too_many_frames = bytes.clone();
+    too_many_frames[frames].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(InputRecording::<GgrsConfig>::read_from(too_many_frames.as_slice()).is_err());
+
+    let mut no_players = too_many_frames.clone();
+    no_players[players].copy_from_slice(&0u32.to_le_bytes());
+    assert!(InputRecording::<GgrsConfig>::read_from(no_players.as_slice()).is_err());
+
+    assert_eq!(
+        InputRecording::<GgrsConfig>::read_from(bytes.as_slice())?.len(),
+        1
+    );
+
+    Ok(())
+}
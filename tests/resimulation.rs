@@ -0,0 +1,84 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration, utils::HashMap};
+use bevy_ggrs::{prelude::*, *};
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+/// How many times each frame was simulated for the first time, and resimulated.
+#[derive(Resource, Default)]
+struct Simulated {
+    first: HashMap<i32, u32>,
+    resimulated: HashMap<i32, u32>,
+}
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn first_simulation(frame: Res<RollbackFrameCount>, mut simulated: ResMut<Simulated>) {
+    *simulated.first.entry((*frame).into()).or_default() += 1;
+}
+
+fn resimulation(frame: Res<RollbackFrameCount>, mut simulated: ResMut<Simulated>) {
+    *simulated.resimulated.entry((*frame).into()).or_default() += 1;
+}
+
+/// This test makes sure that every frame is simulated for the first time exactly once, and that
+/// rollbacks are reported as resimulation.
+#[test]
+fn first_simulation_runs_once_per_frame() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Simulated>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(
+            GgrsSchedule,
+            (
+                first_simulation.run_if(is_first_simulation()),
+                resimulation.run_if(is_resimulating()),
+            )
+                .chain(),
+        );
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>());
+    let highest = i32::from(*app.world.resource::<HighestSimulatedFrame>());
+    assert_eq!(frame, highest);
+
+    let simulated = app.world.resource::<Simulated>();
+
+    assert_eq!(simulated.first.len(), frame as usize);
+    assert!(simulated.first.values().all(|&count| count == 1));
+
+    // A sync test resimulates every frame once it is far enough behind the current frame
+    assert!(!simulated.resimulated.is_empty());
+    assert!(simulated
+        .resimulated
+        .keys()
+        .all(|resimulated| simulated.first.contains_key(resimulated)));
+
+    Ok(())
+}
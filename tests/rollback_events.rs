@@ -0,0 +1,137 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Clone)]
+struct FrameEvent(i32);
+
+#[derive(Resource, Default, Clone, Copy, Debug)]
+struct Received {
+    count: u32,
+    mismatches: u32,
+}
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn send_events(mut events: RollbackEventWriter<FrameEvent>, frame: Res<RollbackFrameCount>) {
+    events.send(FrameEvent((*frame).into()));
+}
+
+fn read_events(
+    events: RollbackEventReader<FrameEvent>,
+    frame: Res<RollbackFrameCount>,
+    mut received: ResMut<Received>,
+) {
+    let frame: i32 = (*frame).into();
+
+    for &FrameEvent(sent) in events.iter() {
+        received.count += 1;
+
+        if sent != frame - 1 {
+            received.mismatches += 1;
+        }
+    }
+}
+
+/// This test makes sure that rollback events are read exactly once per simulated frame,
+/// even when frames are resimulated.
+#[test]
+fn rollback_events_survive_rollback() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(4)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Received>()
+        .rollback_resource_with_copy::<Received>()
+        .add_rollback_event::<FrameEvent>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, (read_events, send_events).chain());
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let frame: i32 = (*app.world.resource::<RollbackFrameCount>()).into();
+    let received = *app.world.resource::<Received>();
+
+    assert!(frame > 10, "Simulation did not advance");
+    assert_eq!(
+        received.mismatches, 0,
+        "Events were read in the wrong frame"
+    );
+    assert_eq!(
+        received.count as i32,
+        frame - 1,
+        "Every frame but the first should read exactly one event"
+    );
+
+    Ok(())
+}
+
+/// This test makes sure that events are discarded once read, even by sessions which never save
+/// a snapshot, such as a replay.
+#[test]
+fn rollback_events_are_discarded_without_snapshots() -> Result<(), Box<dyn std::error::Error>> {
+    let mut recording = InputRecording::<GgrsConfig>::default();
+
+    for frame in 1..=60 {
+        recording.record(frame, &[(0, InputStatus::Confirmed)]);
+    }
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::Replay(ReplaySession::new(recording)))
+        .init_resource::<Received>()
+        .add_rollback_event::<FrameEvent>()
+        .add_systems(GgrsSchedule, (read_events, send_events).chain());
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let frame: i32 = (*app.world.resource::<RollbackFrameCount>()).into();
+    let received = *app.world.resource::<Received>();
+
+    assert!(frame > 10, "Replay did not advance");
+    assert_eq!(
+        received.mismatches, 0,
+        "Events were read in the wrong frame"
+    );
+    assert!(
+        app.world.resource::<RollbackEvents<FrameEvent>>().len() <= 2,
+        "Events were not discarded"
+    );
+
+    Ok(())
+}
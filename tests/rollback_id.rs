@@ -0,0 +1,98 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration, utils::HashMap};
+use bevy_ggrs::{prelude::*, *};
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Component, Clone, Copy, Hash)]
+struct Health(u32);
+
+/// A local-only entity, such as a sound effect.
+#[derive(Component)]
+struct Sound;
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn_players(mut commands: Commands) {
+    commands.spawn(Health(10)).add_rollback();
+    commands.spawn(Health(20)).add_rollback();
+}
+
+fn spawn_projectiles(mut commands: Commands, frame: Res<RollbackFrameCount>) {
+    if i32::from(*frame) % 4 != 0 {
+        return;
+    }
+
+    commands.spawn(Health(1)).add_rollback();
+    commands.spawn(Health(2)).add_rollback();
+}
+
+fn damage(mut query: Query<&mut Health>) {
+    for mut health in query.iter_mut() {
+        health.0 = health.0.saturating_sub(1);
+    }
+}
+
+fn build_app(local_entities: usize) -> Result<App, Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    // Spawn and despawn local entities, so rollback entities end up with different `Entity`s
+    for _ in 0..local_entities {
+        let entity = app.world.spawn(Sound).id();
+        app.world.despawn(entity);
+    }
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .set_rollback_id_scheme(RollbackIdScheme::SpawnOrder)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .rollback_component_with_copy::<Health>()
+        .checksum_component_with_hash::<Health>()
+        .add_systems(Startup, spawn_players)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, (spawn_projectiles, damage).chain());
+
+    Ok(app)
+}
+
+/// This test makes sure that checksums agree across peers which spawn local entities
+/// differently, when using spawn order based rollback ids.
+#[test]
+fn spawn_order_ids_ignore_local_entities() -> Result<(), Box<dyn std::error::Error>> {
+    let mut first = build_app(0)?;
+    let mut second = build_app(5)?;
+
+    for _ in 0..20 {
+        first.update();
+        second.update();
+
+        assert_eq!(
+            first.world.resource::<Checksum>().0,
+            second.world.resource::<Checksum>().0
+        );
+    }
+
+    let mut health = first.world.query::<&Health>();
+    assert!(health.iter(&first.world).count() > 2);
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration};
+use bevy_ggrs::*;
+use ggrs::*;
+
+type TestConfig = bevy_ggrs::GgrsConfig<u8, std::net::SocketAddr>;
+
+#[derive(Component, Clone, Copy)]
+struct Player;
+
+#[derive(Component, Clone, Copy)]
+struct Lifetime(u32);
+
+fn input_system(mut commands: Commands, local_players: Res<LocalPlayers>) {
+    let local_inputs = local_players.0.iter().map(|&handle| (handle, 0)).collect();
+
+    commands.insert_resource(LocalInputs::<TestConfig>(local_inputs));
+}
+
+fn spawn_player(mut commands: Commands) {
+    commands.spawn(Player).add_rollback();
+}
+
+fn spawn_projectile(mut commands: Commands) {
+    commands.spawn(Lifetime(3)).add_rollback();
+}
+
+fn expire_projectiles(mut commands: Commands, mut query: Query<(Entity, &mut Lifetime)>) {
+    for (entity, mut lifetime) in query.iter_mut() {
+        lifetime.0 -= 1;
+
+        if lifetime.0 == 0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn create_app(session: Session<TestConfig>) -> App {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<TestConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(session)
+        .rollback_component_with_copy::<Player>()
+        .rollback_component_with_copy::<Lifetime>()
+        .add_systems(Startup, spawn_player)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(
+            GgrsSchedule,
+            (expire_projectiles, apply_deferred, spawn_projectile).chain(),
+        );
+
+    app
+}
+
+fn create_p2p_app(
+    local: (PlayerHandle, u16),
+    remote: (PlayerHandle, u16),
+) -> Result<App, Box<dyn std::error::Error>> {
+    let remote_address = format!("127.0.0.1:{}", remote.1).parse()?;
+    let socket = UdpNonBlockingSocket::bind_to_port(local.1)?;
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(2)
+        .add_player(PlayerType::Local, local.0)?
+        .add_player(PlayerType::Remote(remote_address), remote.0)?
+        .start_p2p_session(socket)?;
+
+    Ok(create_app(Session::P2P(session)))
+}
+
+/// Asserts that despawned projectiles were pruned, while the player kept its place.
+fn assert_pruned(app: &mut App) {
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>());
+    assert!(frame > 100, "Simulation did not advance");
+
+    let mut player = app.world.query_filtered::<&Rollback, With<Player>>();
+    let player = *player.single(&app.world);
+
+    let rollback_ordered = app.world.resource::<RollbackOrdered>();
+
+    assert_eq!(rollback_ordered.index(player), 0);
+    assert!(
+        (rollback_ordered.len() as i32) < frame / 2,
+        "{} Rollbacks are still registered after {frame} frames",
+        rollback_ordered.len()
+    );
+
+    for rollback in rollback_ordered.iter_sorted() {
+        assert!(rollback_ordered.index(rollback) < rollback_ordered.next_index());
+
+        #[allow(deprecated)]
+        let order = rollback_ordered.order(rollback);
+
+        assert_eq!(order, rollback_ordered.index(rollback));
+    }
+}
+
+/// This test makes sure that despawned entities are eventually forgotten by [`RollbackOrdered`],
+/// without changing the order of the entities which remain.
+#[test]
+fn despawned_rollbacks_are_pruned() -> Result<(), Box<dyn std::error::Error>> {
+    let mut first = create_p2p_app((0, 8093), (1, 8094))?;
+    let mut second = create_p2p_app((1, 8094), (0, 8093))?;
+
+    for _ in 0..200 {
+        first.update();
+        second.update();
+    }
+
+    assert_pruned(&mut first);
+    assert_pruned(&mut second);
+
+    Ok(())
+}
+
+/// This test makes sure that despawned entities are also pruned during a SyncTest session, where
+/// every frame is resimulated.
+#[test]
+fn despawned_rollbacks_are_pruned_in_sync_test() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = create_app(Session::SyncTest(session));
+
+    for _ in 0..200 {
+        app.update();
+    }
+
+    assert_pruned(&mut app);
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+struct Team(u8);
+
+#[derive(Resource, Default, Clone, Copy, Debug)]
+struct Observed {
+    changes: u32,
+    simulation_changes: u32,
+}
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn(mut commands: Commands) {
+    commands.spawn(Team(1)).add_rollback();
+}
+
+fn observe(
+    frame: Res<RollbackFrameCount>,
+    mut observed: ResMut<Observed>,
+    query: Query<(Ref<Team>, Option<&RolledBack<Team>>)>,
+) {
+    let frame: i32 = (*frame).into();
+
+    // Teams are only changed by the simulation when spawned
+    if frame <= 1 {
+        return;
+    }
+
+    for (team, rolled_back) in query.iter() {
+        if team.is_changed() {
+            observed.changes += 1;
+        }
+
+        if RolledBack::is_simulation_change(&team, rolled_back) {
+            observed.simulation_changes += 1;
+        }
+    }
+}
+
+fn create_app(track: bool) -> Result<App, Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Observed>()
+        .rollback_component_with_copy::<Team>()
+        .add_systems(Startup, spawn)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, observe);
+
+    if track {
+        app.track_rolled_back::<Team>();
+    }
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    Ok(app)
+}
+
+/// This test makes sure that changes made by loading a snapshot can be told apart from
+/// changes made by the simulation.
+#[test]
+fn rolled_back_marks_restoration_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let app = create_app(true)?;
+    let observed = *app.world.resource::<Observed>();
+
+    assert!(
+        observed.changes > 0,
+        "Rollbacks should trigger change detection"
+    );
+    assert_eq!(
+        observed.simulation_changes, 0,
+        "Restoration writes should not count as simulation changes"
+    );
+
+    Ok(())
+}
+
+/// This test makes sure that the [`RolledBack`] marker is only inserted when tracked.
+#[test]
+fn rolled_back_is_opt_in() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app(false)?;
+
+    let mut query = app.world.query::<&RolledBack<Team>>();
+
+    assert_eq!(query.iter(&app.world).count(), 0);
+    assert!(
+        app.world.resource::<Observed>().changes > 0,
+        "Rollbacks should still trigger change detection"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration, utils::HashMap};
+use bevy_ggrs::{prelude::*, *};
+use ggrs::*;
+
+type TestConfig = bevy_ggrs::GgrsConfig<u8, std::net::SocketAddr>;
+
+/// The session states entered, in order.
+#[derive(Resource, Default)]
+struct Entered(Vec<GgrsSessionState>);
+
+/// The number of updates during which the session was running.
+#[derive(Resource, Default)]
+struct RunningUpdates(u32);
+
+fn input_system(mut commands: Commands, local_players: Res<LocalPlayers>) {
+    let local_inputs: HashMap<_, _> = local_players.0.iter().map(|&handle| (handle, 0)).collect();
+
+    commands.insert_resource(LocalInputs::<TestConfig>(local_inputs));
+}
+
+fn enter(state: GgrsSessionState) -> impl Fn(ResMut<Entered>) {
+    move |mut entered: ResMut<Entered>| entered.0.push(state)
+}
+
+fn count_running_updates(mut running: ResMut<RunningUpdates>) {
+    running.0 += 1;
+}
+
+fn create_app() -> App {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<TestConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .init_resource::<Entered>()
+        .init_resource::<RunningUpdates>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(Update, count_running_updates.run_if(is_session_running()))
+        .add_systems(
+            OnEnter(GgrsSessionState::Synchronizing),
+            enter(GgrsSessionState::Synchronizing),
+        )
+        .add_systems(
+            OnEnter(GgrsSessionState::Running),
+            enter(GgrsSessionState::Running),
+        )
+        .add_systems(
+            OnEnter(GgrsSessionState::Disconnected),
+            enter(GgrsSessionState::Disconnected),
+        );
+
+    app
+}
+
+fn session_state(app: &App) -> GgrsSessionState {
+    *app.world.resource::<State<GgrsSessionState>>().get()
+}
+
+/// This test makes sure that the session state follows a session being inserted and removed.
+#[test]
+fn session_state_follows_session() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_app();
+
+    app.update();
+    assert_eq!(session_state(&app), GgrsSessionState::NoSession);
+
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(1)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    app.insert_resource(Session::SyncTest(session));
+    app.update();
+    assert_eq!(session_state(&app), GgrsSessionState::Running);
+
+    app.world.remove_resource::<Session<TestConfig>>();
+    app.update();
+    assert_eq!(session_state(&app), GgrsSessionState::Disconnected);
+
+    assert_eq!(
+        app.world.resource::<Entered>().0,
+        vec![GgrsSessionState::Running, GgrsSessionState::Disconnected]
+    );
+    assert_eq!(app.world.resource::<RunningUpdates>().0, 1);
+
+    Ok(())
+}
+
+/// This test makes sure that a P2P session is synchronizing until its remote peer responds.
+#[test]
+fn p2p_session_synchronizes() -> Result<(), Box<dyn std::error::Error>> {
+    // Nothing is listening on the remote port
+    let remote_address = "127.0.0.1:8096".parse()?;
+    let socket = UdpNonBlockingSocket::bind_to_port(8095)?;
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(2)
+        .add_player(PlayerType::Local, 0)?
+        .add_player(PlayerType::Remote(remote_address), 1)?
+        .start_p2p_session(socket)?;
+
+    let mut app = create_app();
+
+    app.insert_resource(Session::P2P(session));
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    assert_eq!(session_state(&app), GgrsSessionState::Synchronizing);
+    assert_eq!(
+        app.world.resource::<Entered>().0,
+        vec![GgrsSessionState::Synchronizing]
+    );
+    assert_eq!(app.world.resource::<RunningUpdates>().0, 0);
+
+    Ok(())
+}
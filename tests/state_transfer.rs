@@ -0,0 +1,332 @@
+#![cfg(feature = "serialize")]
+
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, Instant},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+use std::net::{SocketAddr, UdpSocket};
+
+type TestConfig = bevy_ggrs::GgrsConfig<u8, SocketAddr>;
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component)]
+struct FrameComponent(i32);
+
+#[derive(Resource, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Resource)]
+struct Counter(i32);
+
+/// Despawned early on, so the host has registered more [`Rollback`] entities than it transfers.
+#[derive(Component)]
+struct Temporary;
+
+fn input_system(mut commands: Commands, local_players: Res<LocalPlayers>) {
+    let local_inputs = local_players.0.iter().map(|&handle| (handle, 0)).collect();
+
+    commands.insert_resource(LocalInputs::<TestConfig>(local_inputs));
+}
+
+fn spawn(mut commands: Commands) {
+    commands.spawn(Temporary).add_rollback();
+    commands.spawn(FrameComponent::default()).add_rollback();
+}
+
+fn despawn_temporary(
+    mut commands: Commands,
+    frame: Res<RollbackFrameCount>,
+    query: Query<Entity, With<Temporary>>,
+) {
+    if i32::from(*frame) == 5 {
+        for entity in query.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// The frame of the last completed [`StateTransferEvent`].
+#[derive(Resource, Default)]
+struct Completed(Option<i32>);
+
+fn collect_completed(
+    mut events: EventReader<StateTransferEvent>,
+    mut completed: ResMut<Completed>,
+) {
+    for event in events.iter() {
+        match event {
+            StateTransferEvent::Started { .. } => {}
+            StateTransferEvent::Completed { frame } => completed.0 = Some(*frame),
+            StateTransferEvent::Failed(error) => panic!("State transfer failed: {error}"),
+        }
+    }
+}
+
+fn frame_component_id(app: &mut App) -> u64 {
+    let rollback = *app
+        .world
+        .query_filtered::<&Rollback, With<FrameComponent>>()
+        .single(&app.world);
+
+    app.world.resource::<RollbackOrdered>().id(rollback)
+}
+
+fn update(
+    frame: Res<RollbackFrameCount>,
+    mut counter: ResMut<Counter>,
+    mut query: Query<&mut FrameComponent>,
+) {
+    counter.0 += 1;
+
+    for mut component in query.iter_mut() {
+        component.0 = (*frame).into();
+    }
+}
+
+fn create_app() -> App {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<TestConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .register_type::<FrameComponent>()
+        .register_type::<Counter>()
+        .init_resource::<Counter>()
+        .rollback_component_with_reflect::<FrameComponent>()
+        .rollback_resource_with_reflect::<Counter>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, update);
+
+    app
+}
+
+fn start_p2p_session(
+    local: (PlayerHandle, u16),
+    remote: (PlayerHandle, u16),
+) -> Result<Session<TestConfig>, BoxedError> {
+    let remote_address = format!("127.0.0.1:{}", remote.1).parse()?;
+    let socket = UdpNonBlockingSocket::bind_to_port(local.1)?;
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(2)
+        .add_player(PlayerType::Local, local.0)?
+        .add_player(PlayerType::Remote(remote_address), remote.0)?
+        .start_p2p_session(socket)?;
+
+    Ok(Session::P2P(session))
+}
+
+/// This test makes sure that a peer can join a game in progress by receiving a snapshot
+/// over a socket and resuming the simulation from it.
+#[test]
+fn joining_peer_resumes_from_transferred_state() -> Result<(), BoxedError> {
+    let mut host = create_app();
+    let session = SessionBuilder::<TestConfig>::new()
+        .with_num_players(1)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    host.insert_resource(Session::SyncTest(session))
+        .add_systems(Startup, spawn)
+        .add_systems(GgrsSchedule, despawn_temporary.before(update));
+
+    for _ in 0..30 {
+        host.update();
+    }
+
+    let snapshot = export_confirmed_state(&host.world).expect("No frame confirmed");
+    let frame = snapshot.frame;
+
+    assert!(frame > 5, "Temporary entity should be despawned");
+    assert_eq!(snapshot.entities.len(), 1);
+
+    // Send the snapshot to the joining peer
+    let bytes = {
+        let registry = host.world.resource::<AppTypeRegistry>().read();
+        snapshot.to_transfer_bytes(&registry)?
+    };
+
+    let chunks = StateTransferChunk::split(1, &bytes, 64);
+    assert!(
+        chunks.len() > 1,
+        "Snapshot should be sent in multiple chunks"
+    );
+
+    let host_socket = UdpSocket::bind("127.0.0.1:0")?;
+    let joiner_socket = UdpSocket::bind("127.0.0.1:0")?;
+    joiner_socket.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut assembler = StateTransferAssembler::default();
+    let mut buffer = [0; 2048];
+    let mut received = None;
+
+    for chunk in chunks.iter().rev() {
+        host_socket.send_to(&chunk.to_bytes(), joiner_socket.local_addr()?)?;
+
+        let (length, _) = joiner_socket.recv_from(&mut buffer)?;
+        let chunk = StateTransferChunk::from_bytes(&buffer[..length]).expect("Invalid chunk");
+        received = assembler.receive(chunk);
+    }
+
+    let received = received.expect("Transfer should be complete");
+
+    // Resume both peers from the transferred frame
+    let mut joiner = create_app();
+    let snapshot = {
+        let registry = joiner.world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        WorldSnapshot::from_transfer_bytes(&received, &registry)?
+    };
+
+    import_state(&mut joiner.world, &snapshot)?;
+
+    host.world.remove_resource::<Session<TestConfig>>();
+    rewind_to_frame(&mut host.world, frame)?;
+
+    assert_eq!(*joiner.world.resource::<Counter>(), Counter(frame));
+    assert_eq!(*host.world.resource::<Counter>(), Counter(frame));
+    assert_eq!(
+        frame_component_id(&mut joiner),
+        frame_component_id(&mut host),
+        "Rollback ids should be transferred"
+    );
+
+    host.insert_resource(start_p2p_session((0, 8091), (1, 8092))?);
+    joiner.insert_resource(start_p2p_session((1, 8092), (0, 8091))?);
+
+    for _ in 0..100 {
+        host.update();
+        joiner.update();
+    }
+
+    for app in [&mut host, &mut joiner] {
+        let current = i32::from(*app.world.resource::<RollbackFrameCount>());
+
+        assert!(current > frame, "Simulation did not resume");
+        assert_eq!(
+            *app.world.resource::<Counter>(),
+            Counter(current),
+            "Counter should continue from the transferred state"
+        );
+
+        let mut query = app.world.query::<&FrameComponent>();
+        assert_eq!(query.get_single(&app.world)?, &FrameComponent(current));
+    }
+
+    Ok(())
+}
+
+/// Assert that the simulation of the [`App`] continued from the transferred frame.
+fn assert_resumed(app: &mut App, frame: i32) -> Result<(), BoxedError> {
+    let current = i32::from(*app.world.resource::<RollbackFrameCount>());
+
+    assert_eq!(app.world.resource::<SessionStartFrame>().0, frame);
+    assert!(current > frame, "Simulation did not resume");
+    assert_eq!(
+        *app.world.resource::<Counter>(),
+        Counter(current),
+        "Counter should continue from the transferred state"
+    );
+
+    let mut query = app.world.query::<&FrameComponent>();
+    assert_eq!(query.get_single(&app.world)?, &FrameComponent(current));
+
+    Ok(())
+}
+
+/// This test makes sure that a peer which restarted can rejoin a P2P game in progress through
+/// the [`StateTransferPlugin`], with both peers continuing from the transferred frame.
+#[test]
+fn peer_rejoins_through_the_state_transfer_protocol() -> Result<(), BoxedError> {
+    let mut host = create_app();
+    let mut peer = create_app();
+
+    for app in [&mut host, &mut peer] {
+        app.add_systems(Startup, spawn)
+            .add_systems(GgrsSchedule, despawn_temporary.before(update));
+    }
+
+    host.insert_resource(start_p2p_session((0, 8085), (1, 8086))?);
+    peer.insert_resource(start_p2p_session((1, 8086), (0, 8085))?);
+
+    for _ in 0..60 {
+        host.update();
+        peer.update();
+    }
+
+    assert!(
+        i32::from(*host.world.resource::<ConfirmedFrameCount>()) > 5,
+        "No frames were confirmed"
+    );
+
+    // The peer restarts with an empty world, and asks the host for the state of the game
+    drop(peer);
+
+    host.add_plugins(StateTransferPlugin::<TestConfig>::default())
+        .init_resource::<Completed>()
+        .add_systems(Update, collect_completed)
+        .insert_resource(
+            StateTransferHost::<TestConfig>::new(
+                UdpStateTransferSocket::bind_to_port(8087)?,
+                |_: &SocketAddr| start_p2p_session((0, 8085), (1, 8086)),
+            )
+            .with_chunk_size(64),
+        );
+
+    let mut joiner = create_app();
+
+    joiner
+        .add_plugins(StateTransferPlugin::<TestConfig>::default())
+        .init_resource::<Completed>()
+        .add_systems(Update, collect_completed)
+        .insert_resource(StateTransferJoin::<TestConfig>::new(
+            UdpStateTransferSocket::bind_to_port(8088)?,
+            "127.0.0.1:8087".parse()?,
+            |_: &SocketAddr| start_p2p_session((1, 8086), (0, 8085)),
+        ));
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+
+    let frame = loop {
+        host.update();
+        joiner.update();
+
+        let completed = [&host, &joiner].map(|app| app.world.resource::<Completed>().0);
+
+        if let [Some(frame), Some(joined)] = completed {
+            assert_eq!(frame, joined, "Peers should continue from the same frame");
+
+            let resumed = [&host, &joiner]
+                .iter()
+                .all(|app| i32::from(*app.world.resource::<RollbackFrameCount>()) > frame + 10);
+
+            if resumed {
+                break frame;
+            }
+        }
+
+        assert!(Instant::now() < deadline, "Peers did not resume in time");
+    };
+
+    assert!(frame > 5, "Temporary entity should be despawned");
+    assert!(joiner
+        .world
+        .resource::<StateTransferJoin<TestConfig>>()
+        .is_joined());
+    assert!(!host
+        .world
+        .resource::<StateTransferHost<TestConfig>>()
+        .is_transferring());
+    assert_eq!(
+        frame_component_id(&mut joiner),
+        frame_component_id(&mut host),
+        "Rollback ids should be transferred"
+    );
+
+    assert_resumed(&mut host, frame)?;
+    assert_resumed(&mut joiner, frame)
+}
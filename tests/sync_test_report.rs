@@ -0,0 +1,101 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Component, Reflect, Default, Clone, Copy, Hash, PartialEq, Debug)]
+#[reflect(Component)]
+struct Nondeterministic(u32);
+
+#[derive(Resource, Default)]
+struct Reported(Vec<String>);
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn(mut commands: Commands) {
+    commands.spawn(Nondeterministic::default()).add_rollback();
+}
+
+/// Uses state which is not rolled back, so resimulated frames will differ.
+fn update(mut calls: Local<u32>, mut query: Query<&mut Nondeterministic>) {
+    *calls += 1;
+
+    for mut component in query.iter_mut() {
+        component.0 = *calls;
+    }
+}
+
+fn collect_mismatches(
+    mut mismatches: EventReader<SyncTestMismatch>,
+    mut reported: ResMut<Reported>,
+) {
+    for mismatch in mismatches.iter() {
+        reported.0.extend(
+            mismatch
+                .differences
+                .iter()
+                .filter_map(|difference| match difference {
+                    SnapshotDifference::Component { type_name, .. } => Some(type_name.clone()),
+                    _ => None,
+                }),
+        );
+    }
+}
+
+/// This test makes sure that SyncTest mismatches report the component which differs.
+#[test]
+fn sync_test_mismatch_reports_component() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_plugins(SyncTestReportPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Reported>()
+        .rollback_component_with_reflect::<Nondeterministic>()
+        .checksum_component_with_hash::<Nondeterministic>()
+        .add_systems(Startup, spawn)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, update)
+        .add_systems(Update, collect_mismatches);
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let reported = &app.world.resource::<Reported>().0;
+
+    assert!(!reported.is_empty(), "No mismatch was reported");
+    assert!(
+        reported
+            .iter()
+            .all(|type_name| type_name.ends_with("Nondeterministic")),
+        "Only the nondeterministic component should differ"
+    );
+
+    Ok(())
+}
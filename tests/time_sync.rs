@@ -0,0 +1,44 @@
+use bevy_ggrs::*;
+
+/// This test makes sure that the default policy matches the previous hardcoded behaviour.
+#[test]
+fn run_slow_uses_settings() {
+    let mut settings = RollbackTimingSettings::default();
+    let mut policy = RunSlowTimeSync;
+
+    assert_eq!(policy.frame_time_factor(0, &settings), 1.);
+    assert_eq!(policy.frame_time_factor(3, &settings), 1.1);
+
+    settings.run_slow_factor = 1.25;
+    assert_eq!(policy.frame_time_factor(1, &settings), 1.25);
+}
+
+/// This test makes sure that proportional slowdown scales with the frame advantage.
+#[test]
+fn proportional_scales_with_frames_ahead() {
+    let settings = RollbackTimingSettings::default();
+    let mut policy = ProportionalTimeSync {
+        slowdown_per_frame: 0.05,
+        max_factor: 1.2,
+    };
+
+    assert_eq!(policy.frame_time_factor(-2, &settings), 1.);
+    assert!((policy.frame_time_factor(2, &settings) - 1.1).abs() < 1e-9);
+    assert_eq!(policy.frame_time_factor(10, &settings), 1.2);
+}
+
+/// This test makes sure that frame skipping waits once, then cools down.
+#[test]
+fn frame_skip_waits_then_cools_down() {
+    let settings = RollbackTimingSettings::default();
+    let mut policy = FrameSkipTimeSync::new(2, 3);
+
+    assert_eq!(policy.frame_time_factor(1, &settings), 1.);
+    assert_eq!(policy.frame_time_factor(3, &settings), 4.);
+
+    for _ in 0..3 {
+        assert_eq!(policy.frame_time_factor(3, &settings), 1.);
+    }
+
+    assert_eq!(policy.frame_time_factor(3, &settings), 4.);
+}
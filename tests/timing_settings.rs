@@ -0,0 +1,213 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+use std::num::NonZeroUsize;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+/// The input received for each simulated frame.
+#[derive(Resource, Default)]
+struct Received(HashMap<i32, u8>);
+
+fn input_system(mut commands: Commands, mut counter: Local<u8>) {
+    *counter += 1;
+
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, *counter);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn receive(
+    frame: Res<RollbackFrameCount>,
+    inputs: Res<PlayerInputs<GgrsConfig>>,
+    mut received: ResMut<Received>,
+) {
+    received.0.insert((*frame).into(), inputs[0].0);
+}
+
+/// This test makes sure that input delay and FPS can be changed while a session is running.
+#[test]
+fn timing_settings_apply_at_runtime() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Received>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, receive);
+
+    app.world
+        .resource_mut::<RollbackTimingSettings>()
+        .input_delay = 3;
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let received = &app.world.resource::<Received>().0;
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>());
+
+    assert!(frame > 10, "Simulation did not advance");
+    assert_eq!(
+        received[&1], 1,
+        "Delayed frames should repeat the first input"
+    );
+    assert_eq!(received[&10], 7, "Inputs should be delayed by 3 frames");
+
+    // Halving the FPS should halve the rate the simulation advances at
+    app.world.resource_mut::<RollbackTimingSettings>().fps = 30;
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    let advanced = i32::from(*app.world.resource::<RollbackFrameCount>()) - frame;
+    assert!(
+        (9..=11).contains(&advanced),
+        "Advanced {advanced} frames in 20 updates at 30 FPS"
+    );
+
+    Ok(())
+}
+
+#[derive(Resource, Default)]
+struct Skipped(Vec<FramesSkipped>);
+
+fn record_skipped(mut events: EventReader<FramesSkipped>, mut skipped: ResMut<Skipped>) {
+    skipped.0.extend(events.iter().copied());
+}
+
+fn create_catch_up_app(policy: CatchUpPolicy) -> Result<App, Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    // Every update takes as long as 4.5 frames
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            4.5 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Received>()
+        .init_resource::<Skipped>()
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, receive)
+        .add_systems(Update, record_skipped);
+
+    let mut settings = app.world.resource_mut::<RollbackTimingSettings>();
+    settings.max_steps_per_update = NonZeroUsize::new(2);
+    settings.catch_up_policy = policy;
+
+    Ok(app)
+}
+
+/// This test makes sure that the number of frames simulated per update can be limited, and
+/// that skipped frames are reported.
+#[test]
+fn catch_up_is_limited() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_catch_up_app(CatchUpPolicy::Discard)?;
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>());
+    assert!(frame <= 20, "Simulated {frame} frames in 10 updates");
+
+    let skipped = &app.world.resource::<Skipped>().0;
+    assert!(!skipped.is_empty(), "Skipped frames were not reported");
+    assert!(skipped
+        .iter()
+        .all(|skipped| skipped.frames >= 2 && skipped.policy == CatchUpPolicy::Discard));
+
+    Ok(())
+}
+
+/// This test makes sure that carried over frames are simulated during later updates, are not
+/// reported as skipped, and cannot accumulate beyond a single update's worth.
+#[test]
+fn catch_up_is_carried_over() -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = create_catch_up_app(CatchUpPolicy::CarryOver)?;
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>());
+    assert!(frame <= 20, "Simulated {frame} frames in 10 updates");
+
+    let skipped = app.world.resource::<Skipped>().0.len();
+    assert!(skipped > 0, "Dropped frames were not reported");
+    assert!(app
+        .world
+        .resource::<Skipped>()
+        .0
+        .iter()
+        .all(|skipped| skipped.policy == CatchUpPolicy::CarryOver));
+
+    // Without any new time passing, only the carried over frames are simulated, and no more
+    // frames are reported as skipped
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    let advanced = i32::from(*app.world.resource::<RollbackFrameCount>()) - frame;
+    assert!(
+        (1..=2).contains(&advanced),
+        "Advanced {advanced} frames from carried over time"
+    );
+    assert_eq!(app.world.resource::<Skipped>().0.len(), skipped);
+
+    Ok(())
+}
+
+/// This test makes sure that an app without a session still resets its frame counters when the
+/// number of frames per update is limited.
+#[test]
+fn limited_catch_up_without_session_resets_frames() {
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            4.5 / 60.0,
+        )))
+        .insert_resource(SessionStartFrame(10));
+
+    app.world
+        .resource_mut::<RollbackTimingSettings>()
+        .max_steps_per_update = NonZeroUsize::new(1);
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(i32::from(*app.world.resource::<RollbackFrameCount>()), 10);
+}
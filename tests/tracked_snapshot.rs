@@ -0,0 +1,166 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Component, Clone, Copy, Hash, PartialEq, Debug)]
+struct Team(u8);
+
+#[derive(Component, Default, Clone, Copy, Hash, PartialEq, Debug)]
+struct Health(u32);
+
+#[derive(Component, Default, Clone, Copy, Hash, PartialEq, Debug)]
+struct Parity(u8);
+
+#[derive(Resource, Default)]
+struct Observed {
+    team_changes: u32,
+    mismatches: usize,
+}
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn(mut commands: Commands) {
+    commands.spawn((Team(1), Health(0))).add_rollback();
+    commands.spawn((Team(2), Health(0))).add_rollback();
+}
+
+fn update(
+    frame: Res<RollbackFrameCount>,
+    mut observed: ResMut<Observed>,
+    mut query: Query<(Ref<Team>, &mut Health)>,
+) {
+    let frame: i32 = (*frame).into();
+
+    for (team, mut health) in query.iter_mut() {
+        // Teams are only changed when spawned
+        if team.is_changed() && frame > 1 {
+            observed.team_changes += 1;
+        }
+
+        health.0 += team.0 as u32;
+    }
+}
+
+fn flip(frame: Res<RollbackFrameCount>, mut query: Query<&mut Parity>) {
+    let frame: i32 = (*frame).into();
+
+    for mut parity in query.iter_mut() {
+        parity.set_if_neq(Parity((frame % 2) as u8));
+    }
+}
+
+fn count_mismatches(mut events: EventReader<SyncTestMismatch>, mut observed: ResMut<Observed>) {
+    observed.mismatches += events.iter().count();
+}
+
+/// This test makes sure that rolling back unchanged components does not trigger change detection.
+#[test]
+fn tracked_snapshots_preserve_change_detection() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(4)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_plugins(SyncTestReportPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Observed>()
+        .rollback_component_with_tracked_copy::<Team>()
+        .rollback_component_with_tracked_copy::<Health>()
+        .checksum_component_with_hash::<Health>()
+        .add_systems(Startup, spawn)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, update)
+        .add_systems(Update, count_mismatches);
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let observed = app.world.resource::<Observed>();
+
+    assert_eq!(observed.mismatches, 0, "Rollback was not deterministic");
+    assert_eq!(
+        observed.team_changes, 0,
+        "Unchanged components should not be marked as changed by a rollback"
+    );
+
+    let frame: i32 = (*app.world.resource::<RollbackFrameCount>()).into();
+    let mut query = app.world.query::<(&Team, &Health)>();
+
+    for (team, health) in query.iter(&app.world) {
+        assert_eq!(health.0, team.0 as u32 * frame as u32);
+    }
+
+    Ok(())
+}
+
+/// This test makes sure that components which change back and forth between snapshots are still
+/// restored correctly, even when the restored value equals the newest snapshot.
+#[test]
+fn tracked_snapshots_restore_changed_components() -> Result<(), Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(3)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .add_plugins(SyncTestReportPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<Observed>()
+        .rollback_component_with_tracked_copy::<Parity>()
+        .checksum_component_with_hash::<Parity>()
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn(Parity::default()).add_rollback();
+        })
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, flip)
+        .add_systems(Update, count_mismatches);
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let observed = app.world.resource::<Observed>();
+
+    assert_eq!(observed.mismatches, 0, "Rollback was not deterministic");
+
+    let frame: i32 = (*app.world.resource::<RollbackFrameCount>()).into();
+    let mut query = app.world.query::<&Parity>();
+
+    for parity in query.iter(&app.world) {
+        assert_eq!(parity.0, (frame % 2) as u8);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,259 @@
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::{Duration, HashMap},
+};
+use bevy_ggrs::*;
+use ggrs::*;
+
+pub struct GgrsConfig;
+impl Config for GgrsConfig {
+    type Input = u8;
+    type State = u8;
+    type Address = usize;
+}
+
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component)]
+struct FrameComponent(i32);
+
+#[derive(Resource, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Resource)]
+struct FrameResource(i32);
+
+fn input_system(mut commands: Commands) {
+    let mut local_inputs = HashMap::new();
+    local_inputs.insert(0, 0);
+
+    commands.insert_resource(LocalInputs::<GgrsConfig>(local_inputs));
+}
+
+fn spawn(mut commands: Commands) {
+    commands.spawn(FrameComponent::default()).add_rollback();
+}
+
+fn update(
+    frame: Res<RollbackFrameCount>,
+    mut resource: ResMut<FrameResource>,
+    mut query: Query<&mut FrameComponent>,
+) {
+    let frame: i32 = (*frame).into();
+
+    resource.0 = frame;
+
+    for mut component in query.iter_mut() {
+        component.0 = frame;
+    }
+}
+
+#[derive(Resource, Default, Clone)]
+struct ClonedResource;
+
+/// Run a SyncTest session for a few frames, returning the latest stored [`WorldSnapshot`].
+fn simulate() -> Result<WorldSnapshot, Box<dyn std::error::Error>> {
+    simulate_with(|_| {})
+}
+
+/// As [`simulate`], with additional configuration applied to the [`App`].
+fn simulate_with(
+    configure: impl FnOnce(&mut App),
+) -> Result<WorldSnapshot, Box<dyn std::error::Error>> {
+    let session = SessionBuilder::<GgrsConfig>::new()
+        .with_num_players(1)
+        .with_check_distance(2)
+        .add_player(PlayerType::Local, 0)?
+        .start_synctest_session()?;
+
+    let mut app = App::new();
+
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(GgrsPlugin::<GgrsConfig>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )))
+        .insert_resource(Session::SyncTest(session))
+        .init_resource::<FrameResource>()
+        .rollback_component_with_reflect::<FrameComponent>()
+        .rollback_resource_with_reflect::<FrameResource>()
+        .add_systems(Startup, spawn)
+        .add_systems(ReadInputs, input_system)
+        .add_systems(GgrsSchedule, update);
+
+    configure(&mut app);
+
+    for _ in 0..20 {
+        app.update();
+    }
+
+    // The most recent snapshot is always taken before advancing to the current frame
+    let frame = i32::from(*app.world.resource::<RollbackFrameCount>()) - 1;
+
+    Ok(WorldSnapshot::from_snapshots(&app.world, frame).expect("Snapshot should be stored"))
+}
+
+/// Write a [`WorldSnapshot`] into a fresh [`App`], asserting it matches the snapshot frame.
+fn assert_restores(snapshot: &WorldSnapshot) -> Result<(), Box<dyn std::error::Error>> {
+    let frame = snapshot.frame;
+    let mut restored = App::new();
+
+    restored
+        .register_type::<FrameComponent>()
+        .register_type::<FrameResource>();
+
+    snapshot.write_to_world(&mut restored.world)?;
+
+    let mut query = restored
+        .world
+        .query_filtered::<&FrameComponent, With<Rollback>>();
+
+    assert_eq!(
+        query.get_single(&restored.world)?,
+        &FrameComponent(frame),
+        "Component should match the snapshot frame"
+    );
+    assert_eq!(
+        restored.world.resource::<FrameResource>(),
+        &FrameResource(frame),
+        "Resource should match the snapshot frame"
+    );
+    assert_eq!(
+        i32::from(*restored.world.resource::<RollbackFrameCount>()),
+        frame
+    );
+
+    Ok(())
+}
+
+/// This test makes sure that a [`WorldSnapshot`] can be written into a fresh world.
+#[test]
+fn world_snapshot_restores_into_new_world() -> Result<(), Box<dyn std::error::Error>> {
+    let snapshot = simulate()?;
+
+    assert_eq!(snapshot.entities.len(), 1);
+    assert_eq!(snapshot.resources.len(), 1);
+
+    assert_restores(&snapshot)
+}
+
+/// This test makes sure that a [`WorldSnapshot`] containing unregistered types is rejected
+/// without modifying the world.
+#[test]
+fn world_snapshot_rejects_unregistered_types() -> Result<(), Box<dyn std::error::Error>> {
+    let snapshot = simulate()?;
+
+    let mut restored = App::new();
+
+    restored.register_type::<FrameResource>();
+
+    let result = snapshot.write_to_world(&mut restored.world);
+
+    assert!(matches!(
+        result,
+        Err(WorldSnapshotError::UnregisteredComponent(_))
+    ));
+    assert_eq!(
+        restored.world.entities().len(),
+        0,
+        "No entity should be spawned"
+    );
+    assert!(!restored.world.contains_resource::<FrameResource>());
+    assert!(!restored.world.contains_resource::<RollbackFrameCount>());
+
+    Ok(())
+}
+
+/// This test makes sure that a [`WorldSnapshot`] is rejected when written into a [`World`] without
+/// an [`AppTypeRegistry`].
+#[test]
+fn world_snapshot_requires_a_type_registry() -> Result<(), Box<dyn std::error::Error>> {
+    let snapshot = simulate()?;
+
+    let mut world = World::new();
+
+    let result = snapshot.write_to_world(&mut world);
+
+    assert!(matches!(
+        result,
+        Err(WorldSnapshotError::MissingTypeRegistry)
+    ));
+    assert_eq!(world.entities().len(), 0, "No entity should be spawned");
+
+    Ok(())
+}
+
+/// This test makes sure that types which cannot be reflected are recorded in the
+/// [`WorldSnapshot`], and that such an incomplete snapshot is rejected.
+#[test]
+fn world_snapshot_rejects_omitted_types() -> Result<(), Box<dyn std::error::Error>> {
+    let snapshot = simulate_with(|app| {
+        app.init_resource::<ClonedResource>()
+            .rollback_resource_with_clone::<ClonedResource>();
+    })?;
+
+    assert_eq!(
+        snapshot.omitted,
+        vec![std::any::type_name::<ClonedResource>().to_owned()]
+    );
+
+    let mut restored = App::new();
+
+    restored
+        .register_type::<FrameComponent>()
+        .register_type::<FrameResource>();
+
+    let result = snapshot.write_to_world(&mut restored.world);
+
+    assert!(matches!(result, Err(WorldSnapshotError::Incomplete(_))));
+    assert_eq!(
+        restored.world.entities().len(),
+        0,
+        "No entity should be spawned"
+    );
+    assert!(!restored.world.contains_resource::<FrameResource>());
+
+    Ok(())
+}
+
+/// This test makes sure that a [`WorldSnapshot`] survives a round trip through RON.
+#[cfg(feature = "serialize")]
+#[test]
+fn world_snapshot_ron_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let snapshot = simulate()?;
+
+    let registry = AppTypeRegistry::default();
+    registry.write().register::<FrameComponent>();
+    registry.write().register::<FrameResource>();
+
+    let ron = snapshot.to_ron(&registry.read())?;
+    let deserialized = WorldSnapshot::from_ron(&ron, &registry.read())?;
+
+    assert_eq!(deserialized.frame, snapshot.frame);
+    assert_eq!(deserialized.entities.len(), snapshot.entities.len());
+    assert_eq!(deserialized.entities[0].entity, snapshot.entities[0].entity);
+    assert_eq!(deserialized.resources.len(), snapshot.resources.len());
+    assert_eq!(deserialized.omitted, snapshot.omitted);
+
+    assert_restores(&deserialized)
+}
+
+/// This test makes sure that a [`WorldSnapshot`] can be saved to and loaded from a file.
+#[cfg(feature = "serialize")]
+#[test]
+fn world_snapshot_save_and_load() -> Result<(), Box<dyn std::error::Error>> {
+    let snapshot = simulate()?;
+
+    let registry = AppTypeRegistry::default();
+    registry.write().register::<FrameComponent>();
+    registry.write().register::<FrameResource>();
+
+    let path = std::env::temp_dir().join(format!(
+        "bevy_ggrs_world_snapshot_{}.ron",
+        std::process::id()
+    ));
+
+    snapshot.save(&path, &registry.read())?;
+    let loaded = WorldSnapshot::load(&path, &registry.read());
+    std::fs::remove_file(&path)?;
+
+    assert_restores(&loaded?)
+}